@@ -1,6 +1,7 @@
 use crate::{
     core::{lethality_to_pen, stack_multiplicative_reduction},
     load_champion::ChampionStats,
+    stat_sheet::StatSheet,
     target::VitalityData,
 };
 
@@ -76,12 +77,7 @@ impl ArmorReducer {
 
 impl From<(&ChampionStats, u8)> for ArmorReducer {
     fn from(tuple: (&ChampionStats, u8)) -> ArmorReducer {
-        let (stats, level) = tuple;
-        return ArmorReducer {
-            flat_armor_pen: lethality_to_pen(stats.lethality, level),
-            percent_armor_pen: stats.percent_armor_pen,
-            ..Default::default()
-        };
+        return (&StatSheet::from(tuple)).into();
     }
 }
 
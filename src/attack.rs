@@ -1,4 +1,7 @@
-use crate::{armor_reducer::ArmorReducer, load_champion::ChampionStats, target::VitalityData};
+use crate::{
+    armor_reducer::ArmorReducer, load_champion::ChampionStats, stat_sheet::StatSheet,
+    target::VitalityData,
+};
 
 use super::core;
 
@@ -19,21 +22,50 @@ impl CritAdjuster {
     }
 }
 
-#[derive(Default, Clone)]
+#[derive(Clone)]
 pub struct BasicAttack {
     pub base_attack_damage: f64,
     pub bonus_attack_damage: f64,
+    pub damage_multiplier: f64, // share of attack damage dealt, ie kalista's 90%
 }
 
-#[derive(Default, Clone)]
+impl Default for BasicAttack {
+    fn default() -> Self {
+        BasicAttack::new(0.0, 0.0)
+    }
+}
+
+#[derive(Clone)]
 pub struct AttackSpeed {
     pub base: f64,
-    pub bonus: f64,
+    pub bonus: f64,       // percent
+    pub ratio: f64,       // scaling of bonus attack speed, usually the base
+    pub cap: Option<f64>, // none when something lifts it, ie lethal tempo
+}
+
+impl Default for AttackSpeed {
+    fn default() -> Self {
+        AttackSpeed::new(0.0, 0.0)
+    }
 }
 
 impl AttackSpeed {
+    pub const CAP: f64 = 2.5;
+
+    pub fn new(base: f64, bonus: f64) -> AttackSpeed {
+        return AttackSpeed {
+            base,
+            bonus,
+            ratio: base,
+            cap: Some(AttackSpeed::CAP),
+        };
+    }
+
     pub fn get_attacks_per_second(&self) -> f64 {
-        return self.base * (1.0 + self.bonus / 100.0);
+        let attacks_per_second = self.base + self.ratio * self.bonus / 100.0;
+        return self
+            .cap
+            .map_or(attacks_per_second, |cap| attacks_per_second.min(cap));
     }
 }
 
@@ -47,10 +79,10 @@ impl BasicAttack {
         crit_adjuster: &Option<(CritAdjuster, CritCalculation)>,
         armor_reducer: Option<&ArmorReducer>,
     ) -> f64 {
-        let damage = self.get_total_attack_damage();
+        let damage = self.get_total_attack_damage() * self.damage_multiplier;
 
         let effective_armor = match armor_reducer {
-            Some(reducer) => reducer.get_effective_armor(&target),
+            Some(reducer) => reducer.get_effective_armor(target),
             None => target.base_armor + target.bonus_armor,
         };
 
@@ -65,36 +97,20 @@ impl BasicAttack {
         Self {
             base_attack_damage,
             bonus_attack_damage,
+            damage_multiplier: 1.0,
         }
     }
 }
 
 impl From<(&ChampionStats, u8)> for BasicAttack {
     fn from(tuple: (&ChampionStats, u8)) -> BasicAttack {
-        let (stats, level) = tuple;
-        let attack_damage = core::stat_at_level(
-            stats.base_attack_damage,
-            stats.attack_damage_per_level,
-            level,
-        );
-        let attack = BasicAttack {
-            base_attack_damage: attack_damage,
-            bonus_attack_damage: stats.bonus_attack_damage,
-        };
-
-        return attack;
+        return (&StatSheet::from(tuple)).into();
     }
 }
 
 impl From<(&ChampionStats, u8)> for AttackSpeed {
     fn from(tuple: (&ChampionStats, u8)) -> AttackSpeed {
-        let (stats, level) = tuple;
-        let bonus_speed = core::stat_at_level(0.0, stats.attack_speed_per_level, level)
-            + stats.bonus_attack_speed;
-        return AttackSpeed {
-            base: stats.attack_speed,
-            bonus: bonus_speed,
-        };
+        return (&StatSheet::from(tuple)).into();
     }
 }
 
@@ -121,6 +137,7 @@ pub fn get_dps(
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
+    use rstest::rstest;
 
     mod basic_attack {
         use super::*;
@@ -144,12 +161,41 @@ mod tests {
 
     #[test]
     fn test_attack_to_attack_per_second() {
-        let speed = AttackSpeed {
-            base: 0.651,
-            bonus: 102.9228,
-        };
+        let speed = AttackSpeed::new(0.651, 102.9228);
         let per_second = speed.get_attacks_per_second();
 
         assert_relative_eq!(1.321027428, per_second);
     }
+
+    #[rstest]
+    // caitlyn's ratio is below her base
+    #[case(0.681, 0.568, 100.0, Some(AttackSpeed::CAP), 1.249)]
+    #[case(0.625, 0.625, 500.0, Some(AttackSpeed::CAP), 2.5)]
+    #[case(0.625, 0.625, 500.0, None, 3.75)]
+    #[case(0.625, 0.0, 100.0, Some(AttackSpeed::CAP), 0.625)]
+    fn test_attack_speed_ratio_and_cap(
+        #[case] base: f64,
+        #[case] ratio: f64,
+        #[case] bonus: f64,
+        #[case] cap: Option<f64>,
+        #[case] expected: f64,
+    ) {
+        let speed = AttackSpeed {
+            base,
+            bonus,
+            ratio,
+            cap,
+        };
+        assert_relative_eq!(expected, speed.get_attacks_per_second(), epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_damage_multiplier() {
+        let attack = BasicAttack {
+            damage_multiplier: 0.9,
+            ..BasicAttack::new(100.0, 0.0)
+        };
+        let damage = attack.get_damage_to_target(&VitalityData::default(), &None, None);
+        assert_relative_eq!(90.0, damage);
+    }
 }
@@ -1,21 +1,31 @@
 use core::fmt;
-use std::{
-    cell::RefCell,
-    collections::HashMap,
-    rc::{Rc, Weak},
-};
+use std::{borrow::Cow, collections::HashMap, mem, sync::Arc};
+
+use serde::Deserialize;
 
 use crate::{
     armor_reducer::ArmorReducer,
-    attack::{BasicAttack, CritAdjuster, CritCalculation},
+    attack::{AttackSpeed, CritAdjuster, CritCalculation},
     core::{resist_damage, stat_at_level},
-    item_effects::{OnHit, OnHitActivation, STATIC_ABILITIES},
+    geometry::{time_to_close, CastRange, Position, TargetShape},
+    item_actives::ItemActive,
+    item_effects::{spellblade_damage, OnHit, SPELLBLADES},
     load_champion::{load_champion_stats, ChampionStats},
-    target::{AbilityEffect, EffectData, EffectResult, EmpowerState, Target, VitalityData},
+    runes::{lifts_attack_speed_cap, on_dash, rune_damage_amplification, Rune},
+    stat_sheet::{HitSheets, StatSheet},
+    summoner_spells::SummonerSpell,
+    target::{
+        AbilityEffect, CrowdControl, DamageType, DotPolicy, EffectData, EffectResult, EmpowerState,
+        StackApplyInfo, StackReset, StatBuff, Target, VitalityData,
+    },
     time_manager::TIME,
+    units::{MinionKind, MonsterKind, TurretKind, UnitKind},
+    world::{EntityId, World},
 };
 
-#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+use super::skill_order::{validate_points, Skill, SkillOrder};
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Deserialize)]
 pub enum AbilityName {
     Q,
     W,
@@ -27,7 +37,21 @@ pub enum AbilityName {
     SpellbladeSheen,
     SpellbladeEssenceReaver,
     SpellbladeDivineSunderer,
+    SpellbladeTrinityForce,
+    SpellbladeLichBane,
+    SpellbladeIcebornGauntlet,
+    Electrocute,
+    PressTheAttack,
+    Summoner(SummonerSpell),
+    ItemActive(ItemActive),
+    MistsEdge,
+}
+impl AbilityName {
+    pub fn is_spellblade(&self) -> bool {
+        SPELLBLADES.iter().any(|(_, name)| name == self)
+    }
 }
+
 impl fmt::Display for AbilityName {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt::Debug::fmt(self, f)
@@ -37,19 +61,26 @@ impl fmt::Display for AbilityName {
 pub struct Champion {
     pub stats: ChampionStats,
     pub level: u8,
-    initial_armor: f64, // base armor before level ups
+    base_stats: ChampionStats, // stats before items and runes
     pub current_health: f64,
     pub abilities: NamedClosures,
     pub crit_info: Option<(CritAdjuster, CritCalculation)>,
     effects: Vec<EffectData>,
+    triggered_abilities: Vec<AbilityEffect>, // reached stack thresholds, cast by the world
     pub on_hit_item_effects: Vec<OnHit>,
-    pub ranks: [u8; 4],
+    pub runes: Vec<Rune>,
+    pub items: Vec<String>,
+    points: [u8; 4], // follows the skill order when there is one, 0 leaving the skill unlearned
+    skill_order: Option<SkillOrder>,
+    pub position: Position,
+    pub kind: UnitKind,
 }
 
 #[derive(Default, Debug, Clone)]
 pub struct CastingData {
     pub charge: f64,
     pub rank: u8,
+    pub bystander: bool, // hit by the ability's area rather than targeted
 }
 
 impl CastingData {
@@ -60,55 +91,161 @@ impl CastingData {
         }
     }
 }
+/**
+ * Ability body, called with the world, the caster and the target
+ */
+pub type AbilityFn = dyn Fn(&mut World, EntityId, EntityId, &CastingData) + Send + Sync;
+
+#[derive(Default)]
 pub struct NamedClosures {
-    pub data:
-        HashMap<AbilityName, Box<dyn Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) -> ()>>,
+    pub data: HashMap<AbilityName, Arc<AbilityFn>>,
+    pub shapes: HashMap<AbilityName, TargetShape>, // abilities without one hit a single target
+    pub ranges: HashMap<AbilityName, CastRange>, // abilities without one (other than autos) reach anywhere
 }
 
 impl Champion {
-    pub fn new_dummy_with_resist(armor: f64, magic_resist: f64) -> Champion {
-        let health = 1000.0;
-        let stats = ChampionStats {
-            health,
-            armor,
-            magic_resist,
-            ..Default::default()
-        };
-
+    /**
+     * Any combatant, at full health for its level. Every constructor comes through here, panicking on
+     * skill points the level can't reach
+     */
+    fn from_stats(
+        kind: UnitKind,
+        stats: ChampionStats,
+        level: u8,
+        points: [u8; 4],
+        abilities: NamedClosures,
+    ) -> Champion {
+        validate_points(points, level)
+            .unwrap_or_else(|error| panic!("invalid skill points {:?}: {}", points, error));
+        let health = stat_at_level(stats.health, stats.health_per_level, level);
         return Champion {
-            level: 1,
+            level,
+            base_stats: stats.clone(),
             stats,
-            initial_armor: armor,
             current_health: health,
-            abilities: NamedClosures {
-                data: HashMap::new(),
-            },
+            abilities,
             on_hit_item_effects: Vec::new(),
+            runes: Vec::new(),
+            items: Vec::new(),
             crit_info: None,
             effects: Vec::new(),
-            ranks: [0, 0, 0, 0],
+            triggered_abilities: Vec::new(),
+            points,
+            skill_order: None,
+            position: Position::default(),
+            kind,
         };
     }
 
+    pub fn new_dummy_with_resist(armor: f64, magic_resist: f64) -> Champion {
+        let stats = ChampionStats {
+            health: 1000.0,
+            armor,
+            magic_resist,
+            ..Default::default()
+        };
+        return Champion::from_stats(
+            UnitKind::Champion,
+            stats,
+            1,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        );
+    }
+
     pub fn new_dummy() -> Champion {
         Champion::new_dummy_with_resist(0.0, 0.0)
     }
 
-    pub fn new(name: String, level: u8, ranks: [u8; 4], abilities: NamedClosures) -> Champion {
+    /**
+     * Champion with the skill points in Q, W, E and R, abilities without any are unlearned
+     */
+    pub fn new(name: String, level: u8, points: [u8; 4], abilities: NamedClosures) -> Champion {
         let stats = load_champion_stats(name);
-        let health = stat_at_level(stats.health, stats.health_per_level, level);
-        let initial_armor = stats.armor;
-        return Champion {
-            level,
-            stats,
-            initial_armor,
-            on_hit_item_effects: Vec::new(),
-            current_health: health,
-            abilities,
-            crit_info: None,
-            effects: Vec::new(),
-            ranks,
-        };
+        return Champion::from_stats(UnitKind::Champion, stats, level, points, abilities);
+    }
+
+    pub fn new_minion(kind: MinionKind, game_time: f64) -> Champion {
+        return Champion::from_stats(
+            UnitKind::Minion(kind),
+            kind.stats_at(game_time),
+            1,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        );
+    }
+
+    /**
+     * Monsters level with the average level of the champions in the game
+     */
+    pub fn new_monster(kind: MonsterKind, average_level: u8) -> Champion {
+        return Champion::from_stats(
+            UnitKind::Monster(kind),
+            (&kind.stats()).into(),
+            average_level,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        );
+    }
+
+    pub fn new_turret(kind: TurretKind) -> Champion {
+        return Champion::from_stats(
+            UnitKind::Turret(kind),
+            kind.stats(),
+            1,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        );
+    }
+
+    /**
+     * Champion whose skill points follow the skill order as it levels up
+     */
+    pub fn new_with_skill_order(
+        name: String,
+        level: u8,
+        skill_order: SkillOrder,
+        abilities: NamedClosures,
+    ) -> Champion {
+        let mut champion = Champion::new(name, level, skill_order.points_at(level), abilities);
+        champion.skill_order = Some(skill_order);
+        return champion;
+    }
+
+    /**
+     * Levels up (or down), gaining the health growth and the skill points of the skill order
+     */
+    pub fn set_level(&mut self, level: u8) {
+        let max_health = self.get_max_health();
+        self.level = level;
+        self.current_health += self.get_max_health() - max_health;
+        match &self.skill_order {
+            Some(skill_order) => self.points = skill_order.points_at(level),
+            None => validate_points(self.points, level).unwrap_or_else(|error| {
+                panic!("invalid skill points {:?}: {}", self.points, error)
+            }),
+        }
+    }
+
+    /**
+     * Skill points spent in Q, W, E and R, from the skill order when there is one
+     */
+    pub fn points(&self) -> [u8; 4] {
+        return self.points;
+    }
+
+    /**
+     * Rank as used by `CastingData` (0 being the first rank), none while the skill is unlearned
+     */
+    pub fn rank(&self, skill: Skill) -> Option<u8> {
+        return self.points[skill.index()].checked_sub(1);
+    }
+
+    /**
+     * Abilities outside of Q, W, E and R are always castable
+     */
+    pub fn has_learned(&self, name: &AbilityName) -> bool {
+        return Skill::from_ability(name).is_none_or(|skill| self.rank(skill).is_some());
     }
 
     pub fn is_ranged(&self) -> bool {
@@ -121,6 +258,17 @@ impl Champion {
     }
 
     pub fn upsert_effect(&mut self, effect: EffectData) -> Option<()> {
+        if let EffectResult::DamageOverTime(dot) = &effect.result {
+            // the running instance's ticks were settled by the world before this cast
+            if matches!(dot.policy, DotPolicy::Refresh) {
+                self.effects.retain(|candidate| candidate != &effect);
+            }
+            self.add_effect(effect);
+            return Some(());
+        }
+        if let EffectResult::StackCounter(_) = &effect.result {
+            return self.upsert_stack_counter(effect);
+        }
         let other_expiry = effect.expiry;
         let mut to_add = effect;
         if let Some(index) = self
@@ -129,9 +277,12 @@ impl Champion {
             .position(|candidate| candidate == &to_add)
         {
             let maybe_expired = self.effects.remove(index);
+            // a still running effect is kept as is (ie an empower on cooldown), only its expiry extends
             if TIME.with(|time| maybe_expired.expiry >= *time.borrow()) {
-                to_add = self.bump_found_effect(maybe_expired)?;
-                to_add.expiry = to_add.expiry.max(other_expiry);
+                to_add = EffectData {
+                    expiry: maybe_expired.expiry.max(other_expiry),
+                    ..maybe_expired
+                };
             }
         }
         self.add_effect(to_add);
@@ -139,161 +290,254 @@ impl Champion {
     }
 
     /**
-     * Bumps the given effect, which entails modifying its run count, or increasing its expiry and / or executing it
-     */
-    fn bump_found_effect(&mut self, effect: EffectData) -> Option<EffectData> {
-        match effect {
-            EffectData {
-                result: EffectResult::ThreeHit(mut three_hit_result),
-                expiry,
-                unique_name,
-            } => {
-                three_hit_result.hit_count += 1;
-                if three_hit_result.hit_count >= 2 {
-                    if let EffectResult::AbilityEffect(AbilityEffect {
-                        attacker,
-                        name,
-                        data,
-                    }) = *three_hit_result.on_third_hit.result
-                    {
-                        Champion::execute_ability(attacker, &name, self, &data);
-                        return None;
-                    } else {
-                        self.upsert_effect(EffectData {
-                            expiry: TIME
-                                .with(|time| *time.borrow() + three_hit_result.on_third_hit.ttl),
-                            unique_name: three_hit_result.on_third_hit.unique_name,
-                            result: *three_hit_result.on_third_hit.result,
-                        });
-                        return None;
-                    }
-                } else {
-                    return Some(EffectData {
-                        expiry,
-                        unique_name,
-                        result: EffectResult::ThreeHit(three_hit_result),
-                    });
+     * Adds stacks to the running counter (or starts a new one), firing its threshold effect when reached
+     */
+    fn upsert_stack_counter(&mut self, effect: EffectData) -> Option<()> {
+        let now = TIME.with(|time| *time.borrow());
+        let EffectData {
+            unique_name,
+            result: EffectResult::StackCounter(incoming),
+            ..
+        } = effect
+        else {
+            return None;
+        };
+        let running = self
+            .effects
+            .iter()
+            .position(|candidate| {
+                candidate.unique_name == unique_name
+                    && matches!(candidate.result, EffectResult::StackCounter(_))
+            })
+            .map(|index| self.effects.remove(index))
+            .and_then(|found| match found.result {
+                EffectResult::StackCounter(counter) => {
+                    let count = counter.count_at(found.expiry, now);
+                    (count > 0).then_some((counter, count))
                 }
+                _ => None,
+            });
+
+        let (mut counter, previous_count) = match running {
+            Some((counter, count)) => (counter, count),
+            None => (incoming, 0),
+        };
+        counter.count = counter
+            .max_stacks
+            .min(previous_count.saturating_add(counter.increment));
+
+        let reached_threshold = counter.threshold.is_some_and(|threshold| {
+            counter.count >= threshold
+                && (previous_count < threshold || counter.reset == StackReset::OnThreshold)
+        });
+        if reached_threshold {
+            if let Some(on_threshold) = counter.on_threshold.clone() {
+                self.fire_threshold_effect(on_threshold);
+            }
+            if counter.reset == StackReset::OnThreshold {
+                return Some(());
             }
-            _ => Some(effect),
         }
+        self.add_effect(EffectData {
+            unique_name,
+            expiry: counter.expiry_from(now),
+            result: EffectResult::StackCounter(counter),
+        });
+        Some(())
     }
 
-    pub fn execute_combo(
-        attacker: Rc<RefCell<Self>>,
-        combo: Vec<(AbilityName, CastingData)>,
-        target: &mut Champion,
-    ) {
-        for (name, data) in combo {
-            Self::execute_ability(Rc::downgrade(&attacker), &name, target, &data);
+    fn fire_threshold_effect(&mut self, on_threshold: StackApplyInfo) {
+        match *on_threshold.result {
+            EffectResult::AbilityEffect(ability) => self.triggered_abilities.push(ability),
+            result => {
+                self.upsert_effect(EffectData {
+                    expiry: TIME.with(|time| *time.borrow() + on_threshold.ttl),
+                    unique_name: on_threshold.unique_name,
+                    result,
+                });
+            }
         }
     }
 
-    fn process_on_hit_effects(
-        attacker_ref: Weak<RefCell<Self>>,
-        on_hit_effects: Vec<EffectData>,
-    ) -> Option<()> {
-        let attacker = attacker_ref.upgrade()?;
+    pub fn target_shape(&self, name: &AbilityName) -> TargetShape {
+        return self.abilities.shapes.get(name).copied().unwrap_or_default();
+    }
 
-        on_hit_effects.into_iter().for_each(|effect| {
-            attacker.borrow_mut().upsert_effect(effect);
-        });
+    /**
+     * Center to center range of the ability, `None` when it has no range limit
+     */
+    pub fn cast_range(&self, name: &AbilityName, casting_data: &CastingData) -> Option<f64> {
+        let attack_range = self.stats.attack_range;
+        return match name {
+            AbilityName::AUTO => Some(attack_range),
+            AbilityName::Summoner(spell) => spell.range(),
+            _ => self
+                .abilities
+                .ranges
+                .get(name)
+                .map(|range| range.value(attack_range, casting_data.charge)),
+        };
+    }
 
-        return Some(());
-    }
-
-    fn process_on_auto_effects(
-        attacker_ref: Weak<RefCell<Self>>,
-        target: &mut Champion,
-    ) -> Option<()> {
-        let attacker = attacker_ref.upgrade()?;
-        let to_cast: Vec<AbilityEffect> = attacker
-            .borrow_mut()
-            .valid_effects_mut()
-            .filter_map(|effect| {
-                let mut out: Option<AbilityEffect> = None;
-                if let EffectResult::EmpowerNextAttack(result) = &mut effect.result {
-                    if let EmpowerState::Active(ability, cd) = &result {
-                        effect.expiry = TIME.with(|time| *time.borrow() + cd);
-                        out = Some(ability.clone());
-                    }
-                    effect.result = EffectResult::EmpowerNextAttack(EmpowerState::Cooldown);
-                }
-                return out;
-            })
-            .collect();
+    pub fn in_range(
+        &self,
+        name: &AbilityName,
+        casting_data: &CastingData,
+        target: &Position,
+    ) -> bool {
+        return self
+            .cast_range(name, casting_data)
+            .is_none_or(|range| self.position.distance(target) <= range);
+    }
 
-        to_cast.iter().for_each(|ability| {
-            Champion::execute_ability(
-                Weak::clone(&ability.attacker),
-                &ability.name,
-                target,
-                &ability.data,
-            );
-        });
-        return Some(());
+    pub fn can_move(&self) -> bool {
+        return !self
+            .crowd_controls()
+            .iter()
+            .any(|kind| kind.prevents_moving());
+    }
+
+    /**
+     * Move speed, or nothing while rooted or unable to act
+     */
+    fn movement_speed(&self) -> f64 {
+        if !self.can_move() {
+            return 0.0;
+        }
+        return self.get_move_speed();
+    }
+
+    /**
+     * Walks towards the destination for the given seconds, returns the distance covered
+     */
+    pub fn move_towards(&mut self, destination: &Position, seconds: f64) -> f64 {
+        let start = self.position;
+        self.position = start.moved_towards(destination, self.movement_speed() * seconds);
+        return start.distance(&self.position);
+    }
+
+    /**
+     * Dashes or blinks up to `max_distance` towards the destination, stopping on it
+     */
+    pub fn dash_towards(&mut self, destination: &Position, max_distance: f64) {
+        self.position = self.position.moved_towards(destination, max_distance);
+        on_dash(self);
     }
 
-    pub fn execute_ability(
-        attacker_ref: Weak<RefCell<Self>>,
+    /**
+     * Seconds of walking before the ability reaches a target standing still, `None` if it never will
+     */
+    pub fn time_to_reach(
+        &self,
         name: &AbilityName,
-        target: &mut Champion,
         casting_data: &CastingData,
+        target: &Position,
     ) -> Option<f64> {
-        let initial_health = target.current_health;
-        let attacker = attacker_ref.upgrade()?;
-        match name {
-            AbilityName::AUTO => {
-                let on_auto_effects: Vec<EffectData> = attacker
-                    .borrow()
-                    .on_hit_item_effects
-                    .iter()
-                    .filter(|effect| matches!(effect.mode, OnHitActivation::Auto))
-                    .map(|on_hit| (on_hit, Weak::clone(&attacker_ref)).into())
-                    .collect();
-                Champion::process_on_hit_effects(Weak::clone(&attacker_ref), on_auto_effects);
-                Champion::process_on_auto_effects(attacker_ref, target);
-            }
-            AbilityName::Q | AbilityName::W | AbilityName::E | AbilityName::R => {
-                let on_auto_effects: Vec<EffectData> = attacker
-                    .borrow()
-                    .on_hit_item_effects
-                    .iter()
-                    .filter(|effect| matches!(effect.mode, OnHitActivation::ActiveSpell))
-                    .map(|on_hit| (on_hit, Weak::clone(&attacker_ref)).into())
-                    .collect();
-                Champion::process_on_hit_effects(Weak::clone(&attacker_ref), on_auto_effects);
-            }
-            _ => {}
-        }
+        let Some(range) = self.cast_range(name, casting_data) else {
+            return Some(0.0);
+        };
+        return time_to_close(
+            self.position.distance(target) - range,
+            self.movement_speed(),
+            0.0,
+        );
+    }
 
-        let binding = attacker.borrow();
-        let maybe_func = binding.abilities.data.get(&name);
-        if let Some(func) = maybe_func {
-            func(target, Rc::clone(&attacker), casting_data);
-        } else {
-            STATIC_ABILITIES.with(|item_abilities| {
-                let func = item_abilities.get(&name).unwrap();
-                func(target, Rc::clone(&attacker), casting_data);
+    /**
+     * Seconds to get within `range` of a champion running straight away, `None` if it gets away
+     */
+    pub fn time_to_catch(&self, fleeing: &Champion, range: f64) -> Option<f64> {
+        return time_to_close(
+            self.position.distance(&fleeing.position) - range,
+            self.movement_speed(),
+            fleeing.movement_speed(),
+        );
+    }
+
+    /**
+     * The ready spellblade that would deal the most damage to the target
+     */
+    pub fn strongest_spellblade(&self, target: &Champion) -> Option<AbilityName> {
+        self.valid_effects()
+            .filter_map(|effect| match &effect.result {
+                EffectResult::EmpowerNextAttack(EmpowerState::Active(ability, _))
+                    if ability.name.is_spellblade() =>
+                {
+                    Some(ability.name.clone())
+                }
+                _ => None,
             })
+            .map(|name| {
+                let damage = spellblade_damage(&name, self, target);
+                (name, damage)
+            })
+            .max_by(|(_, left), (_, right)| left.total_cmp(right))
+            .map(|(name, _)| name)
+    }
+
+    /**
+     * Final stats at the current level with items, runes and active buffs. Built from scratch on every
+     * call, so code reading several stats builds it once
+     */
+    pub fn stat_sheet(&self) -> StatSheet {
+        let mut sheet = StatSheet::new(
+            &self.base_stats,
+            &self.stats,
+            self.level,
+            &self.get_stat_buffs(),
+        );
+        let (bonus_lethality, bonus_magic_pen) = self.get_penetration_buffs();
+        sheet.lethality += bonus_lethality;
+        sheet.flat_magic_pen += bonus_magic_pen;
+        sheet.move_speed.bonus -= sheet.move_speed.total() * self.get_slow() / 100.0;
+        if lifts_attack_speed_cap(self) {
+            sheet.attack_speed_cap = None;
         }
-        let final_health = target.current_health;
-        return Some(initial_health - final_health);
+        return sheet;
     }
 
     pub fn get_base_armor(&self) -> f64 {
-        return stat_at_level(self.initial_armor, self.stats.armor_per_level, self.level);
+        return self.stat_sheet().armor.base;
     }
     pub fn get_bonus_ad(&self) -> f64 {
-        self.stats.bonus_attack_damage
+        return self.stat_sheet().attack_damage.bonus;
+    }
+
+    pub fn get_ability_power(&self) -> f64 {
+        return self.stat_sheet().ability_power.total();
+    }
+
+    pub fn get_attack_speed(&self) -> AttackSpeed {
+        return (&self.stat_sheet()).into();
     }
 
     pub fn get_bonus_armor(&self) -> f64 {
-        return self.stats.armor - self.initial_armor;
+        return self.stat_sheet().armor.bonus;
+    }
+
+    pub fn get_move_speed(&self) -> f64 {
+        return self.stat_sheet().move_speed.total();
+    }
+
+    /**
+     * Sum of the timed stat buffs currently on this champion
+     */
+    pub fn get_stat_buffs(&self) -> StatBuff {
+        self.active_results()
+            .iter()
+            .filter_map(|result| match result.as_ref() {
+                EffectResult::StatBuff(buff) => Some(*buff),
+                _ => None,
+            })
+            .fold(StatBuff::default(), |total, buff| total.add(&buff))
     }
 
     pub fn get_max_health(&self) -> f64 {
-        return stat_at_level(self.stats.health, self.stats.health_per_level, self.level);
+        return self.stat_sheet().health.total();
+    }
+
+    pub fn get_bonus_health(&self) -> f64 {
+        return self.stat_sheet().health.bonus;
     }
 
     pub fn get_missing_health(&self) -> f64 {
@@ -301,35 +545,330 @@ impl Champion {
     }
 
     pub fn get_base_ad(&self) -> f64 {
-        BasicAttack::from((&self.stats, self.level)).base_attack_damage
+        return self.stat_sheet().attack_damage.base;
     }
 
-    fn get_magic_resist(&self) -> f64 {
-        return stat_at_level(
-            self.stats.magic_resist,
-            self.stats.magic_resist_per_level,
-            self.level,
-        );
+    pub fn get_magic_resist(&self) -> f64 {
+        return self.stat_sheet().magic_resist.total();
     }
 
     pub fn full_heal(&mut self) {
         self.current_health = self.get_max_health()
     }
 
+    /**
+     * Restores health, reduced by the strongest grievous wounds on this champion
+     */
+    pub fn heal(&mut self, amount: f64) {
+        let reduction = self
+            .valid_effects()
+            .filter_map(|effect| match effect.result {
+                EffectResult::GrievousWounds(percent) => Some(percent),
+                _ => None,
+            })
+            .fold(0.0, f64::max);
+        self.current_health = self
+            .get_max_health()
+            .min(self.current_health + amount * (1.0 - reduction / 100.0));
+    }
+
     pub fn receive_damage(&mut self, attacker: &Champion, damage: f64) {
-        let mut armor_reducer: ArmorReducer = (&attacker.stats, attacker.level).into();
+        self.receive_typed_damage(attacker, damage, DamageType::Physical)
+    }
+
+    pub fn receive_typed_damage(
+        &mut self,
+        attacker: &Champion,
+        damage: f64,
+        damage_type: DamageType,
+    ) {
+        let mitigated = self.mitigated_damage(attacker, damage, damage_type);
+        self.take_mitigated_damage(mitigated, attacker.execute_threshold());
+    }
+
+    /**
+     * Loses the health, dying outright below the attacker's execute threshold (percent max health)
+     */
+    pub fn take_mitigated_damage(&mut self, damage: f64, execute_threshold: f64) {
+        self.current_health -= damage;
+        // most attackers have no execute, no need for the sheet then
+        if execute_threshold > 0.0
+            && self.current_health < 0.01 * execute_threshold * self.get_max_health()
+        {
+            self.current_health = self.current_health.min(0.0);
+        }
+    }
+
+    /**
+     * Strongest execute threshold among this champion's effects, ie the collector's 5%
+     */
+    pub fn execute_threshold(&self) -> f64 {
         self.valid_effects()
-            .filter_map(|effect| match &effect.result {
-                EffectResult::ArmorReducer(reducer) => Some(reducer),
+            .filter_map(|effect| match effect.result {
+                EffectResult::ExecuteThreshold(percent) => Some(percent),
+                _ => None,
+            })
+            .fold(0.0, f64::max)
+    }
+
+    /**
+     * Damage this champion would take after resists, penetration and amplifiers
+     */
+    pub fn mitigated_damage(
+        &self,
+        attacker: &Champion,
+        damage: f64,
+        damage_type: DamageType,
+    ) -> f64 {
+        let sheets = HitSheets::new(attacker, self);
+        return self.mitigated_damage_with(attacker, &sheets, damage, damage_type);
+    }
+
+    /**
+     * Same as `mitigated_damage`, reading the stats from sheets the caller already built
+     */
+    pub fn mitigated_damage_with(
+        &self,
+        attacker: &Champion,
+        sheets: &HitSheets,
+        damage: f64,
+        damage_type: DamageType,
+    ) -> f64 {
+        let attacker_sheet = &sheets.attacker;
+        let mitigated_damage = match damage_type {
+            DamageType::Physical => {
+                let mut armor_reducer: ArmorReducer = attacker_sheet.into();
+                self.active_results()
+                    .iter()
+                    .filter_map(|result| match result.as_ref() {
+                        EffectResult::ArmorReducer(reducer) => Some(reducer),
+                        _ => None,
+                    })
+                    .for_each(|other_reducer| armor_reducer.add_armor_reducer(other_reducer));
+
+                let target_data = self.vitality_data_with(&sheets.target);
+                let effective_armor = armor_reducer.get_effective_armor(&target_data);
+                resist_damage(damage, effective_armor)
+            }
+            DamageType::Magic => {
+                let mut magic_resist = sheets.target.magic_resist.total()
+                    * (1.0 - attacker_sheet.percent_magic_pen / 100.0);
+                if magic_resist > 0.0 {
+                    magic_resist = (magic_resist - attacker_sheet.flat_magic_pen).max(0.0);
+                }
+                resist_damage(damage, magic_resist)
+            }
+            DamageType::True => damage,
+        };
+        let amplification = rune_damage_amplification(attacker, self, sheets)
+            + self.sum_effects(|result| match result {
+                EffectResult::IncomingDamageAmplifier(percent) => Some(*percent),
                 _ => None,
             })
-            .for_each(|other_reducer| armor_reducer.add_armor_reducer(&other_reducer));
+            + attacker.sum_effects(|result| match result {
+                EffectResult::OutgoingDamageAmplifier(percent) => Some(*percent),
+                _ => None,
+            })
+            + match self.kind {
+                UnitKind::Monster(kind) if !kind.is_epic() => attacker_sheet.monster_damage,
+                _ => 0.0,
+            };
+        let reduction = match attacker.kind {
+            UnitKind::Champion => self.damage_reduction(),
+            _ => 0.0,
+        };
+        return mitigated_damage * (1.0 + amplification / 100.0) * (1.0 - reduction / 100.0);
+    }
+
+    /**
+     * Health and resists as of this moment, from a sheet of this champion
+     */
+    pub fn vitality_data_with(&self, sheet: &StatSheet) -> VitalityData {
+        let mut vitality: VitalityData = sheet.into();
+        vitality.current_health = self.current_health;
+        return vitality;
+    }
+
+    /**
+     * Lethality and flat magic pen from temporary effects (ie sudden impact)
+     */
+    fn get_penetration_buffs(&self) -> (f64, f64) {
+        self.active_results()
+            .iter()
+            .fold((0.0, 0.0), |(lethality, magic_pen), result| {
+                match result.as_ref() {
+                    EffectResult::Penetration {
+                        lethality: effect_lethality,
+                        flat_magic_pen,
+                    } => (lethality + effect_lethality, magic_pen + flat_magic_pen),
+                    _ => (lethality, magic_pen),
+                }
+            })
+    }
 
-        let target_data = self.get_vitality_data();
-        let effective_armor = armor_reducer.get_effective_armor(&target_data);
-        let final_damage = resist_damage(damage, effective_armor);
-        let health = &mut self.current_health;
-        *health = *health - final_damage;
+    /**
+     * Percent less damage this champion deals to the target, ie after hitting the holder of iceborn gauntlet
+     */
+    pub fn damage_reduction_against(&self, target: EntityId) -> f64 {
+        self.sum_effects(|result| match result {
+            EffectResult::DamageReductionAgainst {
+                target: reduced,
+                percent,
+            } if *reduced == target => Some(*percent),
+            _ => None,
+        })
+    }
+
+    fn sum_effects(&self, value: impl Fn(&EffectResult) -> Option<f64>) -> f64 {
+        self.active_results()
+            .iter()
+            .filter_map(|result| value(result))
+            .sum()
+    }
+
+    pub fn has_effect(&self, unique_name: &str) -> bool {
+        self.valid_effects()
+            .any(|effect| effect.unique_name == unique_name)
+    }
+
+    /**
+     * Applies crowd control, shortened by tenacity where it applies
+     */
+    pub fn apply_crowd_control(&mut self, unique_name: &str, kind: CrowdControl, duration: f64) {
+        let mut duration = duration;
+        if kind.reduced_by_tenacity() {
+            duration *= 1.0 - self.stat_sheet().tenacity / 100.0;
+        }
+        self.upsert_effect(EffectData {
+            unique_name: unique_name.to_string(),
+            expiry: TIME.with(|time| *time.borrow() + duration),
+            result: EffectResult::CrowdControl(kind),
+        });
+    }
+
+    pub fn crowd_controls(&self) -> Vec<CrowdControl> {
+        self.valid_effects()
+            .filter_map(|effect| match effect.result {
+                EffectResult::CrowdControl(kind) => Some(kind),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /**
+     * Stunned, knocked up or suppressed, unable to attack or cast
+     */
+    pub fn is_crowd_controlled(&self) -> bool {
+        self.crowd_controls()
+            .iter()
+            .any(|kind| kind.prevents_acting())
+    }
+
+    /**
+     * Seconds until the champion can act again
+     */
+    pub fn crowd_control_remaining(&self) -> f64 {
+        let now = TIME.with(|time| *time.borrow());
+        self.valid_effects()
+            .filter(|effect| {
+                matches!(effect.result, EffectResult::CrowdControl(kind) if kind.prevents_acting())
+            })
+            .map(|effect| effect.expiry - now)
+            .fold(0.0, f64::max)
+    }
+
+    /**
+     * Strongest slow, in percent move speed
+     */
+    pub fn get_slow(&self) -> f64 {
+        self.crowd_controls()
+            .iter()
+            .filter_map(|kind| match kind {
+                CrowdControl::Slow(percent) => Some(*percent),
+                _ => None,
+            })
+            .fold(0.0, f64::max)
+    }
+
+    pub fn is_on_cooldown(&self, unique_name: &str) -> bool {
+        self.valid_effects().any(|effect| {
+            effect.unique_name == unique_name && matches!(effect.result, EffectResult::Cooldown)
+        })
+    }
+
+    pub fn put_on_cooldown(&mut self, unique_name: &str, cooldown: f64) {
+        self.upsert_effect(EffectData {
+            unique_name: unique_name.to_string(),
+            expiry: TIME.with(|time| *time.borrow() + cooldown),
+            result: EffectResult::Cooldown,
+        });
+    }
+
+    /**
+     * Current stack count of the named stacking effect, 0 if expired or absent
+     */
+    pub fn get_stacks(&self, unique_name: &str) -> u8 {
+        let now = TIME.with(|time| *time.borrow());
+        self.valid_effects()
+            .find_map(|effect| match &effect.result {
+                EffectResult::StackCounter(counter) if effect.unique_name == unique_name => {
+                    Some(counter.count_at(effect.expiry, now))
+                }
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /**
+     * Results of every active effect, with stack counters' per stack effects multiplied out
+     */
+    pub fn active_results(&self) -> Vec<Cow<'_, EffectResult>> {
+        let now = TIME.with(|time| *time.borrow());
+        self.valid_effects()
+            .flat_map(|effect| {
+                let stacked = match &effect.result {
+                    EffectResult::StackCounter(counter) => {
+                        counter.stacked_effect(counter.count_at(effect.expiry, now))
+                    }
+                    _ => None,
+                };
+                [Some(Cow::Borrowed(&effect.result)), stacked.map(Cow::Owned)]
+            })
+            .flatten()
+            .collect()
+    }
+
+    /**
+     * Takes the damage over time ticks due by the current time, along with the source dealing them
+     */
+    pub fn take_due_damage(&mut self) -> Vec<(EntityId, f64, DamageType)> {
+        let now = TIME.with(|time| *time.borrow());
+        let due = self
+            .effects
+            .iter_mut()
+            .filter_map(|effect| match &mut effect.result {
+                EffectResult::DamageOverTime(dot) => {
+                    let ticks = dot.take_due_ticks(now);
+                    (ticks > 0).then_some((
+                        dot.source,
+                        dot.tick_damage * ticks as f64,
+                        dot.damage_type,
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+        self.effects.retain(|effect| {
+            !matches!(&effect.result, EffectResult::DamageOverTime(dot) if dot.remaining_ticks == 0)
+        });
+        return due;
+    }
+
+    /**
+     * Abilities whose stack threshold was reached on this champion since the last call
+     */
+    pub fn take_triggered_abilities(&mut self) -> Vec<AbilityEffect> {
+        mem::take(&mut self.triggered_abilities)
     }
 
     pub fn valid_effects(&self) -> impl Iterator<Item = &EffectData> {
@@ -346,12 +885,10 @@ impl Champion {
 
 impl Target for Champion {
     fn get_vitality_data(&self) -> VitalityData {
-        return VitalityData {
-            base_armor: self.get_base_armor(),
-            bonus_armor: self.get_bonus_armor(),
-            magic_resist: self.get_magic_resist(),
-            max_health: self.get_max_health(),
-            current_health: self.current_health,
-        };
+        return self.vitality_data_with(&self.stat_sheet());
+    }
+
+    fn unit_kind(&self) -> UnitKind {
+        return self.kind;
     }
 }
@@ -1,4 +1,29 @@
+use self::champion::NamedClosures;
+use crate::jungle::Rotation;
+
 pub mod champion;
 pub mod leblanc;
+pub mod skill_order;
 pub mod vi;
 pub use vi::Vi;
+
+/**
+ * Abilities of the champion, champions without an implementation only get the shared ones (autos, items, ...)
+ */
+pub fn champion_closures(name: &str) -> NamedClosures {
+    match name {
+        Vi::NAME => Vi::new().get_name_closures(),
+        _ => NamedClosures::default(),
+    }
+}
+
+/**
+ * Rotation of the champion by name, ie "ult_combo" for vi
+ */
+pub fn champion_rotation(champion: &str, rotation: &str) -> Option<Rotation> {
+    match (champion, rotation) {
+        (Vi::NAME, "ult_combo") => Some(Vi::ult_combo),
+        (Vi::NAME, "jungle_rotation") => Some(Vi::jungle_rotation),
+        _ => None,
+    }
+}
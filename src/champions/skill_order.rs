@@ -0,0 +1,242 @@
+use std::fmt;
+
+use serde::Deserialize;
+
+use super::champion::AbilityName;
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, Deserialize)]
+pub enum Skill {
+    Q,
+    W,
+    E,
+    R,
+}
+
+impl Skill {
+    pub const ALL: [Skill; 4] = [Skill::Q, Skill::W, Skill::E, Skill::R];
+
+    pub fn index(&self) -> usize {
+        return match self {
+            Skill::Q => 0,
+            Skill::W => 1,
+            Skill::E => 2,
+            Skill::R => 3,
+        };
+    }
+
+    pub fn from_ability(name: &AbilityName) -> Option<Skill> {
+        return match name {
+            AbilityName::Q => Some(Skill::Q),
+            AbilityName::W => Some(Skill::W),
+            AbilityName::E => Some(Skill::E),
+            AbilityName::R => Some(Skill::R),
+            _ => None,
+        };
+    }
+
+    /**
+     * Basic abilities can take a point every other level up to 5, the ultimate at 6, 11 and 16
+     */
+    pub fn max_points_at(&self, level: u8) -> u8 {
+        return match self {
+            Skill::R => match level {
+                0..=5 => 0,
+                6..=10 => 1,
+                11..=15 => 2,
+                _ => 3,
+            },
+            _ => level.div_ceil(2).min(5),
+        };
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum SkillOrderError {
+    TooManyLevels,
+    PointNotAvailable { skill: Skill, level: u8 },
+    NotEnoughPoints { level: u8 },
+}
+
+impl fmt::Display for SkillOrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SkillOrderError::TooManyLevels => write!(f, "more than 18 skill points"),
+            SkillOrderError::PointNotAvailable { skill, level } => {
+                write!(f, "no point available in {:?} at level {}", skill, level)
+            }
+            SkillOrderError::NotEnoughPoints { level } => {
+                write!(f, "more skill points than level {}", level)
+            }
+        }
+    }
+}
+
+/**
+ * Skill order as written in data files, three skills being a max priority
+ */
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SkillOrderData {
+    Priority([Skill; 3]),
+    Order(Vec<Skill>),
+}
+
+impl TryFrom<SkillOrderData> for SkillOrder {
+    type Error = SkillOrderError;
+    fn try_from(data: SkillOrderData) -> Result<SkillOrder, SkillOrderError> {
+        return match data {
+            SkillOrderData::Priority(priority) => Ok(SkillOrder::max_by_priority(priority)),
+            SkillOrderData::Order(order) => SkillOrder::new(order),
+        };
+    }
+}
+
+/**
+ * Skill leveled at each champion level, the first entry being level 1
+ */
+#[derive(PartialEq, Debug, Clone, Deserialize)]
+#[serde(try_from = "SkillOrderData")]
+pub struct SkillOrder {
+    order: Vec<Skill>,
+}
+
+impl SkillOrder {
+    pub fn new(order: Vec<Skill>) -> Result<SkillOrder, SkillOrderError> {
+        if order.len() > 18 {
+            return Err(SkillOrderError::TooManyLevels);
+        }
+        let mut points = [0u8; 4];
+        for (index, skill) in order.iter().enumerate() {
+            let level = index as u8 + 1;
+            points[skill.index()] += 1;
+            if points[skill.index()] > skill.max_points_at(level) {
+                return Err(SkillOrderError::PointNotAvailable {
+                    skill: *skill,
+                    level,
+                });
+            }
+        }
+        return Ok(SkillOrder { order });
+    }
+
+    /**
+     * Ultimate whenever available, one point in each basic ability first, then max them by priority
+     */
+    pub fn max_by_priority(priority: [Skill; 3]) -> SkillOrder {
+        let mut points = [0u8; 4];
+        let mut order = Vec::new();
+        for level in 1..=18 {
+            let can_level = |skill: &Skill, points: &[u8; 4]| {
+                points[skill.index()] < skill.max_points_at(level)
+            };
+            let skill = Some(Skill::R)
+                .filter(|skill| can_level(skill, &points))
+                .or_else(|| {
+                    priority
+                        .into_iter()
+                        .find(|skill| points[skill.index()] == 0)
+                })
+                .or_else(|| priority.into_iter().find(|skill| can_level(skill, &points)))
+                .expect("18 levels fit in the available skill points");
+            points[skill.index()] += 1;
+            order.push(skill);
+        }
+        return SkillOrder { order };
+    }
+
+    /**
+     * Skill points spent in Q, W, E and R at the given level
+     */
+    pub fn points_at(&self, level: u8) -> [u8; 4] {
+        let mut points = [0u8; 4];
+        self.order
+            .iter()
+            .take(level as usize)
+            .for_each(|skill| points[skill.index()] += 1);
+        return points;
+    }
+}
+
+/**
+ * Checks skill points given without a skill order can be spent by the level
+ */
+pub fn validate_points(points: [u8; 4], level: u8) -> Result<(), SkillOrderError> {
+    if let Some(skill) = Skill::ALL
+        .into_iter()
+        .find(|skill| points[skill.index()] > skill.max_points_at(level))
+    {
+        return Err(SkillOrderError::PointNotAvailable { skill, level });
+    }
+    if points.iter().sum::<u8>() > level {
+        return Err(SkillOrderError::NotEnoughPoints { level });
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(vec![Skill::Q, Skill::Q], SkillOrderError::PointNotAvailable { skill: Skill::Q, level: 2 })]
+    #[case(vec![Skill::Q, Skill::W, Skill::E, Skill::Q, Skill::Q, Skill::Q],
+        SkillOrderError::PointNotAvailable { skill: Skill::Q, level: 6 })]
+    #[case(vec![Skill::Q, Skill::W, Skill::E, Skill::R],
+        SkillOrderError::PointNotAvailable { skill: Skill::R, level: 4 })]
+    #[case(vec![Skill::Q; 19], SkillOrderError::TooManyLevels)]
+    fn test_invalid_skill_order(#[case] order: Vec<Skill>, #[case] expected: SkillOrderError) {
+        assert_eq!(Err(expected), SkillOrder::new(order));
+    }
+
+    #[rstest]
+    #[case(1, [0, 0, 1, 0])]
+    #[case(3, [1, 1, 1, 0])]
+    #[case(6, [1, 1, 3, 1])]
+    #[case(11, [3, 1, 5, 2])]
+    #[case(18, [5, 5, 5, 3])]
+    fn test_max_by_priority(#[case] level: u8, #[case] expected: [u8; 4]) {
+        let order = SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W]);
+        assert_eq!(expected, order.points_at(level));
+    }
+
+    #[rstest]
+    fn test_max_by_priority_is_valid() {
+        let order = SkillOrder::max_by_priority([Skill::Q, Skill::E, Skill::W]);
+        assert_eq!(Ok(order.clone()), SkillOrder::new(order.order));
+    }
+
+    #[rstest]
+    fn test_points_at() {
+        let order = SkillOrder::new(vec![Skill::Q, Skill::E, Skill::Q]).unwrap();
+        assert_eq!([1, 0, 1, 0], order.points_at(2));
+        assert_eq!([2, 0, 1, 0], order.points_at(3));
+    }
+
+    #[rstest]
+    #[case([0, 0, 0, 0], 1, Ok(()))]
+    #[case([1, 1, 3, 1], 6, Ok(()))]
+    #[case([2, 0, 0, 0], 1, Err(SkillOrderError::PointNotAvailable { skill: Skill::Q, level: 1 }))]
+    #[case([0, 0, 0, 1], 5, Err(SkillOrderError::PointNotAvailable { skill: Skill::R, level: 5 }))]
+    #[case([0, 0, 0, 1], 6, Ok(()))]
+    #[case([3, 3, 0, 0], 5, Err(SkillOrderError::NotEnoughPoints { level: 5 }))]
+    fn test_validate_points(
+        #[case] points: [u8; 4],
+        #[case] level: u8,
+        #[case] expected: Result<(), SkillOrderError>,
+    ) {
+        assert_eq!(expected, validate_points(points, level));
+    }
+
+    #[rstest]
+    fn test_deserialize() {
+        let priority: SkillOrder = serde_json::from_str(r#"["E", "Q", "W"]"#).unwrap();
+        assert_eq!(
+            SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W]),
+            priority
+        );
+        let order: SkillOrder = serde_json::from_str(r#"["Q", "E", "Q", "W"]"#).unwrap();
+        assert_eq!([2, 0, 1, 0], order.points_at(3));
+        assert!(serde_json::from_str::<SkillOrder>(r#"["R", "Q", "E", "W"]"#).is_err());
+    }
+}
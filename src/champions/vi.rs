@@ -1,13 +1,24 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     armor_reducer::ArmorReducer,
     attack::BasicAttack,
+    core::linear_by_level,
+    geometry::{CastRange, TargetShape},
     item_effects::STATIC_ABILITIES,
-    target::{AbilityEffect, EffectResult, ThreeHit, ThreeHitApplyInfo, VitalityData},
+    stat_sheet::{HitSheets, StatSheet},
+    summoner_spells::SummonerSpell,
+    target::{
+        AbilityEffect, CrowdControl, DamageType, EffectResult, StackApplyInfo, StackCounter,
+        Target, VitalityData,
+    },
+    world::{EntityId, World},
 };
 
-use super::champion::{AbilityName, CastingData, Champion, NamedClosures};
+use super::{
+    champion::{AbilityFn, AbilityName, CastingData, NamedClosures},
+    skill_order::Skill,
+};
 
 pub struct Vi {
     q_data: AbiltyDamageInfo,
@@ -16,19 +27,76 @@ pub struct Vi {
     r_data: AbiltyDamageInfo,
 }
 
+/**
+ * Damage increased linearly with the target's missing health, ie "increased by up to 100% based on missing health"
+ */
+#[derive(Default, Clone, Copy, Debug)]
+pub struct MissingHealthAmplification {
+    pub max_percent: f64,
+    pub at_missing_health_percent: f64, // missing health at which max_percent is reached
+}
+
+impl MissingHealthAmplification {
+    pub fn multiplier(&self, target: &VitalityData) -> f64 {
+        let missing_percent = 100.0 * (1.0 - target.current_health / target.max_health);
+        let progress = (missing_percent / self.at_missing_health_percent).clamp(0.0, 1.0);
+        return 1.0 + 0.01 * self.max_percent * progress;
+    }
+}
+
 #[derive(Default, Clone, Copy)]
 pub struct AbiltyDamageInfo {
     pub base_damages: [f64; 5],
-    pub target_max_health_ratio: [f64; 5], // stored as percent (0-100)
-    pub ad_ratio: f64,
-    pub bonus_ad_ratio: f64,
+    pub base_damage_by_level: Option<(f64, f64)>, // level 1 to 18 damage, added to the rank's base
+    // ratios below are stored as percent (0-100) per rank
+    pub ad_ratio: [f64; 5],
+    pub bonus_ad_ratio: [f64; 5],
+    pub ap_ratio: [f64; 5],
+    pub bonus_health_ratio: [f64; 5],
+    pub armor_ratio: [f64; 5],
+    pub bonus_armor_ratio: [f64; 5],
+    pub magic_resist_ratio: [f64; 5],
+    pub target_max_health_ratio: [f64; 5],
+    pub target_current_health_ratio: [f64; 5],
+    pub target_missing_health_ratio: [f64; 5],
+    pub missing_health_amplification: Option<MissingHealthAmplification>,
 }
 
 impl AbiltyDamageInfo {
-    pub fn to_damage_amount(&self, rank: u8, base: f64, bonus: f64) -> f64 {
-        return self.base_damages[rank as usize]
-            + 0.01 * self.ad_ratio * (base + bonus)
-            + 0.01 * self.bonus_ad_ratio * bonus;
+    /**
+     * Raw damage from the caster's sheet against the target as it is at the moment of the hit
+     */
+    pub fn to_damage_amount(&self, rank: u8, sheet: &StatSheet, target: &VitalityData) -> f64 {
+        let rank = rank as usize;
+        let base_ad = sheet.attack_damage.base;
+        let bonus_ad = sheet.attack_damage.bonus;
+        let bonus_armor = sheet.armor.bonus;
+        let missing_health = target.max_health - target.current_health;
+        let level_damage = self
+            .base_damage_by_level
+            .map_or(0.0, |(start, end)| linear_by_level(start, end, sheet.level));
+        let damage = self.base_damages[rank]
+            + level_damage
+            + 0.01 * self.ad_ratio[rank] * (base_ad + bonus_ad)
+            + 0.01 * self.bonus_ad_ratio[rank] * bonus_ad
+            + 0.01 * self.ap_ratio[rank] * sheet.ability_power.total()
+            + 0.01 * self.bonus_health_ratio[rank] * sheet.health.bonus
+            + 0.01 * self.armor_ratio[rank] * sheet.armor.total()
+            + 0.01 * self.bonus_armor_ratio[rank] * bonus_armor
+            + 0.01 * self.magic_resist_ratio[rank] * sheet.magic_resist.total()
+            + 0.01 * self.target_max_health_ratio[rank] * target.max_health
+            + 0.01 * self.target_current_health_ratio[rank] * target.current_health
+            + 0.01 * self.target_missing_health_ratio[rank] * missing_health;
+        return damage
+            * self
+                .missing_health_amplification
+                .map_or(1.0, |amplification| amplification.multiplier(target));
+    }
+}
+
+impl Default for Vi {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -42,95 +110,137 @@ impl Vi {
     const Q_MAX_DAMAGE_CHARGE: f64 = 1.25;
 
     const W_HP_SCALING: [f64; 5] = [4.0, 5.5, 7.0, 8.5, 10.0];
+    const W_MONSTER_CAP: f64 = 300.0;
 
     const E_DAMAGE: [f64; 5] = [0.0, 15.0, 30.0, 45.0, 60.0];
     const R_DAMAGE: [f64; 5] = [150.0, 325.0, 350.0, 0.0, 0.0];
+    const R_BYSTANDER_DAMAGE: f64 = 0.75;
+    const R_NAME: &str = "Cease and Desist";
+
+    // e blasts through its target in a cone, r knocks aside everyone on the way to its target
+    pub const E_SHAPE: TargetShape = TargetShape::Cone {
+        range: 600.0,
+        angle: 45.0,
+    };
+    pub const R_SHAPE: TargetShape = TargetShape::Path { width: 150.0 };
+
+    // q's dash grows with charge, e empowers the next attack
+    pub const Q_RANGE: CastRange = CastRange::Charged {
+        min: 250.0,
+        max: 725.0,
+        full_charge: Vi::Q_MAX_DAMAGE_CHARGE,
+    };
+    pub const E_RANGE: CastRange = CastRange::AttackRange;
+    pub const R_RANGE: CastRange = CastRange::Fixed(800.0);
 
     pub fn new() -> Vi {
         Vi {
             w_data: AbiltyDamageInfo {
-                bonus_ad_ratio: 1.0 / 35.0, // percent ad
+                bonus_ad_ratio: [1.0 / 35.0; 5], // percent ad
                 target_max_health_ratio: Vi::W_HP_SCALING,
                 ..Default::default()
             },
             e_data: AbiltyDamageInfo {
                 base_damages: Vi::E_DAMAGE,
-                ad_ratio: 120.0,
+                ad_ratio: [120.0; 5],
                 ..Default::default()
             },
             r_data: AbiltyDamageInfo {
                 base_damages: Vi::R_DAMAGE,
-                bonus_ad_ratio: 110.0,
+                bonus_ad_ratio: [110.0; 5],
                 ..Default::default()
             },
 
             q_data: AbiltyDamageInfo {
                 base_damages: Vi::Q_DAMAGE,
-                bonus_ad_ratio: 80.0,
+                bonus_ad_ratio: [80.0; 5],
                 ..Default::default()
             },
         }
     }
 
     pub fn get_name_closures(&mut self) -> NamedClosures {
-        let mut map: HashMap<
-            AbilityName,
-            Box<dyn Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) -> ()>,
-        > = HashMap::new();
+        let mut map: HashMap<AbilityName, Arc<AbilityFn>> = HashMap::new();
         map.entry(AbilityName::Q)
-            .or_insert(Box::new(Vi::ability_q(self.q_data)));
+            .or_insert(Arc::new(Vi::ability_q(self.q_data)));
         map.entry(AbilityName::WPassive)
-            .or_insert(Box::new(Vi::ability_w(self.w_data)));
+            .or_insert(Arc::new(Vi::ability_w(self.w_data)));
         map.entry(AbilityName::E)
-            .or_insert(Box::new(Vi::ability_e(self.e_data)));
+            .or_insert(Arc::new(Vi::ability_e(self.e_data)));
         map.entry(AbilityName::R)
-            .or_insert(Box::new(Vi::ability_r(self.r_data)));
+            .or_insert(Arc::new(Vi::ability_r(self.r_data)));
 
         map.entry(AbilityName::AUTO)
-            .or_insert(Box::new(Vi::auto_attack()));
-
-        return NamedClosures { data: map };
+            .or_insert(Arc::new(Vi::auto_attack()));
+
+        let shapes = HashMap::from([(AbilityName::E, Vi::E_SHAPE), (AbilityName::R, Vi::R_SHAPE)]);
+        let ranges = HashMap::from([
+            (AbilityName::Q, Vi::Q_RANGE),
+            (AbilityName::E, Vi::E_RANGE),
+            (AbilityName::R, Vi::R_RANGE),
+        ]);
+        return NamedClosures {
+            data: map,
+            shapes,
+            ranges,
+        };
     }
 
     pub fn ability_q(
         q_data: AbiltyDamageInfo,
-    ) -> impl Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) {
-        return move |target: &mut Champion,
-                     attacker: Rc<RefCell<Champion>>,
+    ) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+        return move |world: &mut World,
+                     attacker: EntityId,
+                     target: EntityId,
                      casting_data: &CastingData| {
             const MAX_SCALE: f64 = 1.0;
             let rank = casting_data.rank;
             let percent_damage = MAX_SCALE.min(casting_data.charge * 0.10 / 0.125) + 1.0;
-            let base_ad = attacker.borrow().get_base_ad();
-            let bonus_ad = attacker.borrow().get_bonus_ad();
-
-            let mut raw_damage = q_data.to_damage_amount(rank, base_ad, bonus_ad);
+            let destination = world[target].position;
+            let range = world[attacker].cast_range(&AbilityName::Q, casting_data);
+            // stops on the first champion hit
+            world[attacker].dash_towards(&destination, range.unwrap_or(f64::INFINITY));
+            let sheets = HitSheets::new(&world[attacker], &world[target]);
+            let target_data = world[target].vitality_data_with(&sheets.target);
+            let mut raw_damage = q_data.to_damage_amount(rank, &sheets.attacker, &target_data);
             raw_damage *= percent_damage;
-            target.receive_damage(&attacker.borrow(), raw_damage);
-            Vi::apply_w_effect(target, attacker);
+            world.deal_typed_damage_with(
+                attacker,
+                target,
+                &sheets,
+                raw_damage,
+                DamageType::Physical,
+            );
+            Vi::apply_w_effect(world, attacker, target);
         };
     }
 
-    pub fn apply_w_effect(target: &mut Champion, attacker: Rc<RefCell<Champion>>) {
-        ThreeHit::upsert_to_champ(
-            target,
-            ThreeHitApplyInfo {
+    pub fn apply_w_effect(world: &mut World, attacker: EntityId, target: EntityId) {
+        let Some(rank) = world[attacker].rank(Skill::W) else {
+            return;
+        };
+        StackCounter::on_nth_hit(
+            3,
+            StackApplyInfo {
                 unique_name: "Denting Blows Damage".to_string(),
                 result: Box::new(EffectResult::AbilityEffect(AbilityEffect {
-                    attacker: Rc::downgrade(&attacker),
+                    attacker,
                     name: AbilityName::WPassive,
                     data: CastingData {
-                        rank: attacker.borrow().ranks[1],
+                        rank,
                         ..Default::default()
                     },
                 })),
                 ttl: 0.0,
             },
             4.0,
-        );
-        ThreeHit::upsert_to_champ(
-            target,
-            ThreeHitApplyInfo {
+        )
+        .upsert_to_champ(&mut world[target], "Denting Blows Damage");
+        // the damage lands before the armor shred
+        world.fire_triggered_abilities(target);
+        StackCounter::on_nth_hit(
+            3,
+            StackApplyInfo {
                 unique_name: "Denting Blows Armor".to_string(),
                 result: Box::new(EffectResult::ArmorReducer(ArmorReducer {
                     percent_armor_reduction: 20.0,
@@ -140,111 +250,195 @@ impl Vi {
             },
             4.0,
         )
+        .upsert_to_champ(&mut world[target], "Denting Blows Armor")
     }
 
     pub fn ability_w(
         w_data: AbiltyDamageInfo,
-    ) -> impl Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) {
-        return move |target: &mut Champion,
-                     attacker: Rc<RefCell<Champion>>,
+    ) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+        return move |world: &mut World,
+                     attacker: EntityId,
+                     target: EntityId,
                      casting_data: &CastingData| {
-            let bonus_ad = attacker.borrow().get_bonus_ad();
+            let sheets = HitSheets::new(&world[attacker], &world[target]);
             let rank = casting_data.rank;
             let percent_health_dmg = 0.01 * w_data.target_max_health_ratio[rank as usize]
-                + 0.01 * w_data.bonus_ad_ratio * bonus_ad;
-            let raw_damage = percent_health_dmg * target.get_max_health();
-            target.receive_damage(&attacker.borrow(), raw_damage);
+                + 0.01 * w_data.bonus_ad_ratio[rank as usize] * sheets.attacker.attack_damage.bonus;
+            let mut raw_damage = percent_health_dmg * sheets.target.health.total();
+            if world[target].unit_kind().is_monster() {
+                raw_damage = raw_damage.min(Vi::W_MONSTER_CAP);
+            }
+            world.deal_typed_damage_with(
+                attacker,
+                target,
+                &sheets,
+                raw_damage,
+                DamageType::Physical,
+            );
         };
     }
 
     pub fn ability_e(
         e_data: AbiltyDamageInfo,
-    ) -> impl Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) {
-        return move |target: &mut Champion,
-                     attacker: Rc<RefCell<Champion>>,
+    ) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+        return move |world: &mut World,
+                     attacker: EntityId,
+                     target: EntityId,
                      casting_data: &CastingData| {
             let rank = casting_data.rank;
-            let bonus_ad = attacker.borrow().get_bonus_ad();
-            let base_ad = attacker.borrow().get_base_ad();
-            let e_dmg = e_data.to_damage_amount(rank, base_ad, bonus_ad);
+            let sheets = HitSheets::new(&world[attacker], &world[target]);
+            let target_data = world[target].vitality_data_with(&sheets.target);
+            let e_dmg = e_data.to_damage_amount(rank, &sheets.attacker, &target_data);
             let attack = BasicAttack::new(e_dmg, 0.0);
             let raw_damage = attack.get_damage_to_target(
                 &VitalityData::default(),
-                &attacker.borrow().crit_info,
+                &world[attacker].crit_info,
                 None,
             );
-            target.receive_damage(&attacker.borrow(), raw_damage);
-            Vi::apply_w_effect(target, attacker);
+            world.deal_typed_damage_with(
+                attacker,
+                target,
+                &sheets,
+                raw_damage,
+                DamageType::Physical,
+            );
+            Vi::apply_w_effect(world, attacker, target);
         };
     }
 
     pub fn ability_r(
         r_data: AbiltyDamageInfo,
-    ) -> impl Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) {
-        return move |target: &mut Champion,
-                     attacker: Rc<RefCell<Champion>>,
+    ) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+        return move |world: &mut World,
+                     attacker: EntityId,
+                     target: EntityId,
                      casting_data: &CastingData| {
             let rank = casting_data.rank;
-            let bonus_ad = attacker.borrow().get_bonus_ad();
-            let base_ad = attacker.borrow().get_base_ad();
-
-            let raw_damage = r_data.to_damage_amount(rank, base_ad, bonus_ad);
-
-            target.receive_damage(&attacker.borrow(), raw_damage)
+            if !casting_data.bystander {
+                // unstoppable, always reaches its target
+                let destination = world[target].position;
+                world[attacker].dash_towards(&destination, f64::INFINITY);
+            }
+            let sheets = HitSheets::new(&world[attacker], &world[target]);
+            let target_data = world[target].vitality_data_with(&sheets.target);
+            let mut raw_damage = r_data.to_damage_amount(rank, &sheets.attacker, &target_data);
+            if casting_data.bystander {
+                raw_damage *= Vi::R_BYSTANDER_DAMAGE;
+                world[target].apply_crowd_control(Vi::R_NAME, CrowdControl::Knockup, 0.75);
+            } else {
+                world[target].apply_crowd_control(Vi::R_NAME, CrowdControl::Knockup, 1.3);
+            }
+
+            world.deal_typed_damage_with(
+                attacker,
+                target,
+                &sheets,
+                raw_damage,
+                DamageType::Physical,
+            );
         };
     }
 
-    pub fn auto_attack() -> impl Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) {
-        return move |target: &mut Champion,
-                     attacker: Rc<RefCell<Champion>>,
-                     _casting_data: &CastingData| {
-            STATIC_ABILITIES.with(|abilities| {
-                let func = abilities.get(&AbilityName::AUTO).unwrap();
-                func(target, Rc::clone(&attacker), _casting_data);
-            });
-            Vi::apply_w_effect(target, attacker);
+    pub fn auto_attack() -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+        return move |world: &mut World,
+                     attacker: EntityId,
+                     target: EntityId,
+                     casting_data: &CastingData| {
+            let func = STATIC_ABILITIES.get(&AbilityName::AUTO).unwrap();
+            func(world, attacker, target, casting_data);
+            Vi::apply_w_effect(world, attacker, target);
         };
     }
 
-    pub fn ult_combo(ranks: [u8; 4]) -> Vec<(AbilityName, CastingData)> {
+    /**
+     * Cast through `World::execute_combo`, which fills in the ranks the caster has learned
+     */
+    pub fn ult_combo() -> Vec<(AbilityName, CastingData)> {
         //q , auto , e , (w), ult, auto, e
-        let mut out = Vec::new();
-
-        out.push((
-            AbilityName::Q,
-            CastingData {
-                rank: ranks[0],
-                charge: Vi::Q_MAX_DAMAGE_CHARGE,
-            },
-        ));
-        out.push((AbilityName::AUTO, CastingData::new(0)));
+        let mut out = vec![
+            (
+                AbilityName::Q,
+                CastingData {
+                    charge: Vi::Q_MAX_DAMAGE_CHARGE,
+                    ..Default::default()
+                },
+            ),
+            (AbilityName::AUTO, CastingData::new(0)),
+        ];
 
         out.push((
             AbilityName::E,
             CastingData {
-                rank: ranks[2],
                 charge: Vi::Q_MAX_DAMAGE_CHARGE,
+                ..Default::default()
             },
         ));
 
         out.push((
             AbilityName::R,
             CastingData {
-                rank: ranks[1],
                 charge: Vi::Q_MAX_DAMAGE_CHARGE,
+                ..Default::default()
             },
         ));
         out.push((AbilityName::AUTO, CastingData::new(0)));
         out.push((
             AbilityName::E,
             CastingData {
-                rank: ranks[2],
                 charge: Vi::Q_MAX_DAMAGE_CHARGE,
+                ..Default::default()
             },
         ));
 
         return out;
     }
+
+    /**
+     * Camp opener: charged q in, then both e charges woven between autos
+     */
+    pub fn jungle_rotation() -> Vec<(AbilityName, CastingData)> {
+        let e = (AbilityName::E, CastingData::default());
+        return vec![
+            (
+                AbilityName::Q,
+                CastingData {
+                    charge: Vi::Q_MAX_DAMAGE_CHARGE,
+                    ..Default::default()
+                },
+            ),
+            (AbilityName::AUTO, CastingData::new(0)),
+            e.clone(),
+            (AbilityName::AUTO, CastingData::new(0)),
+            e,
+        ];
+    }
+
+    /**
+     * Flash in before the q, damaging summoners straight after the ult lands
+     */
+    pub fn ult_combo_with_summoners(spells: &[SummonerSpell]) -> Vec<(AbilityName, CastingData)> {
+        let mut out = Vi::ult_combo();
+        let (flashes, others): (Vec<SummonerSpell>, Vec<SummonerSpell>) = spells
+            .iter()
+            .partition(|spell| matches!(spell, SummonerSpell::Flash));
+        let after_ult = out
+            .iter()
+            .position(|(name, _)| matches!(name, AbilityName::R))
+            .map_or(out.len(), |index| index + 1);
+        out.splice(
+            after_ult..after_ult,
+            others
+                .into_iter()
+                .map(|spell| (AbilityName::Summoner(spell), CastingData::default())),
+        );
+        out.splice(
+            0..0,
+            flashes
+                .into_iter()
+                .map(|spell| (AbilityName::Summoner(spell), CastingData::default())),
+        );
+        return out;
+    }
 }
 
 #[cfg(test)]
@@ -252,6 +446,16 @@ mod tests {
     use crate::{load_wiki_item::apply_item_to_champ, time_manager::TIME};
 
     use super::*;
+    use crate::{
+        champions::{
+            champion::Champion,
+            skill_order::{Skill, SkillOrder},
+        },
+        geometry::Position,
+        target::DamageType,
+        units::MonsterKind,
+    };
+    use approx::assert_relative_eq;
     use rstest::rstest;
 
     // values sampled from game on 13.7
@@ -268,18 +472,90 @@ mod tests {
     ) {
         let mut vi_data = Vi::new();
         let vi_closures = vi_data.get_name_closures();
-        let mut vi = Champion::new(Vi::NAME.to_string(), level, [0, 0, 0, 0], vi_closures);
+        let mut vi = Champion::new(
+            Vi::NAME.to_string(),
+            level,
+            [rank + 1, 0, 0, 0],
+            vi_closures,
+        );
         vi.stats.bonus_attack_damage += bonus_ad;
 
-        let target = &mut Champion::new_dummy();
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
 
-        Champion::execute_ability(
-            Rc::downgrade(&Rc::new(RefCell::new(vi))),
+        world.execute_ability(
+            vi,
             &AbilityName::Q,
             target,
-            &CastingData { rank, charge },
+            &CastingData {
+                rank,
+                charge,
+                ..Default::default()
+            },
+        );
+        assert_eq!(expected, world[target].get_missing_health().round() as u32);
+    }
+
+    #[rstest]
+    #[case(0, 170.0)]
+    #[case(1, 10.0 + 170.0 + 50.0 + 60.0 + 30.0 + 70.0 + 10.0 + 20.0)]
+    fn test_caster_stat_ratios(#[case] rank: u8, #[case] expected: f64) {
+        let info = AbiltyDamageInfo {
+            base_damages: [0.0, 10.0, 0.0, 0.0, 0.0],
+            base_damage_by_level: Some((0.0, 170.0)),
+            ad_ratio: [0.0, 50.0, 0.0, 0.0, 0.0],
+            ap_ratio: [0.0, 60.0, 0.0, 0.0, 0.0],
+            bonus_health_ratio: [0.0, 10.0, 0.0, 0.0, 0.0],
+            armor_ratio: [0.0, 100.0, 0.0, 0.0, 0.0],
+            bonus_armor_ratio: [0.0, 50.0, 0.0, 0.0, 0.0],
+            magic_resist_ratio: [0.0, 50.0, 0.0, 0.0, 0.0],
+            ..Default::default()
+        };
+        let mut caster = Champion::new_dummy_with_resist(50.0, 40.0);
+        caster.level = 18;
+        caster.stats.base_attack_damage = 100.0;
+        caster.stats.ability_power = 100.0;
+        caster.stats.health += 300.0;
+        caster.stats.armor += 20.0;
+        let damage = info.to_damage_amount(rank, &caster.stat_sheet(), &VitalityData::default());
+        assert!(
+            (expected - damage).abs() < 1e-9,
+            "{} != {}",
+            expected,
+            damage
+        );
+    }
+
+    #[rstest]
+    // full health, half health, at the amplification cap
+    #[case(1000.0, 100.0 + 100.0)]
+    #[case(500.0, (100.0 + 50.0 + 100.0) * (1.0 + 50.0 / 75.0))]
+    #[case(250.0, (100.0 + 25.0 + 150.0) * 2.0)]
+    fn test_health_ratio_damage(#[case] current_health: f64, #[case] expected: f64) {
+        let info = AbiltyDamageInfo {
+            base_damages: [0.0, 0.0, 0.0, 0.0, 0.0],
+            target_max_health_ratio: [10.0, 0.0, 0.0, 0.0, 0.0],
+            target_current_health_ratio: [10.0, 0.0, 0.0, 0.0, 0.0],
+            target_missing_health_ratio: [20.0, 0.0, 0.0, 0.0, 0.0],
+            missing_health_amplification: Some(MissingHealthAmplification {
+                max_percent: 100.0,
+                at_missing_health_percent: 75.0,
+            }),
+            ..Default::default()
+        };
+        let target = VitalityData {
+            max_health: 1000.0,
+            current_health,
+            ..Default::default()
+        };
+        let damage = info.to_damage_amount(0, &Champion::new_dummy().stat_sheet(), &target);
+        assert!(
+            (expected - damage).abs() < 1e-9,
+            "{} != {}",
+            expected,
+            damage
         );
-        assert_eq!(expected, target.get_missing_health().round() as u32);
     }
 
     #[rstest]
@@ -288,54 +564,183 @@ mod tests {
 
         let mut vi_data = Vi::new();
         let vi_closures = vi_data.get_name_closures();
-        let mut vi = Champion::new(Vi::NAME.to_string(), level, [0, 0, 2, 0], vi_closures);
+        let mut vi = Champion::new(Vi::NAME.to_string(), level, [1, 1, 3, 1], vi_closures);
 
         vi.stats.bonus_attack_damage += 40.0;
-        let target = &mut Champion::new_dummy();
-        let ranks = vi.ranks;
-        Champion::execute_combo(Rc::new(RefCell::new(vi)), Vi::ult_combo(ranks), target);
-        assert_eq!(965, target.get_missing_health().round() as u32);
+        assert_eq!(965, combo_damage(vi, Vi::ult_combo()));
         // 905 dirk last whisper 30 armor
     }
 
+    fn combo_damage(vi: Champion, combo: Vec<(AbilityName, CastingData)>) -> u32 {
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
+        world.execute_combo(vi, combo, target);
+        return world[target].get_missing_health().round() as u32;
+    }
+
+    #[rstest]
+    fn test_full_combo_with_skill_order() {
+        let new_vi = |level: u8| {
+            let skill_order = SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W]);
+            let mut vi = Champion::new_with_skill_order(
+                Vi::NAME.to_string(),
+                level,
+                skill_order,
+                Vi::new().get_name_closures(),
+            );
+            vi.stats.bonus_attack_damage += 40.0;
+            vi
+        };
+        let vi = new_vi(6);
+        assert_eq!([1, 1, 3, 1], vi.points());
+        assert_eq!(965, combo_damage(vi, Vi::ult_combo()));
+
+        // no ultimate before level 6
+        let vi = new_vi(3);
+        assert_eq!([1, 1, 1, 0], vi.points());
+        let combo = Vi::ult_combo();
+        let mut without_ult = Champion::new(
+            Vi::NAME.to_string(),
+            3,
+            [1, 1, 1, 0],
+            Vi::new().get_name_closures(),
+        );
+        without_ult.stats.bonus_attack_damage += 40.0;
+        let combo_without_ult = combo
+            .iter()
+            .filter(|(name, _)| *name != AbilityName::R)
+            .cloned()
+            .collect();
+        assert_eq!(
+            combo_damage(without_ult, combo_without_ult),
+            combo_damage(vi, combo)
+        );
+    }
+
+    #[rstest]
+    fn test_unlearned_abilities_deal_no_damage() {
+        let vi = Champion::new(
+            Vi::NAME.to_string(),
+            1,
+            [0, 0, 0, 0],
+            Vi::new().get_name_closures(),
+        );
+        let combo = vec![
+            (AbilityName::Q, CastingData::default()),
+            (AbilityName::R, CastingData::default()),
+        ];
+        assert_eq!(0, combo_damage(vi, combo));
+    }
+
+    #[rstest]
+    // primary target, on the way to it, behind it, behind vi
+    #[case(AbilityName::R, [1.0, Vi::R_BYSTANDER_DAMAGE, 0.0, 0.0])]
+    #[case(AbilityName::E, [1.0, 1.0, 1.0, 0.0])]
+    #[case(AbilityName::Q, [1.0, 0.0, 0.0, 0.0])]
+    fn test_area_abilities(#[case] name: AbilityName, #[case] expected_share: [f64; 4]) {
+        let mut world = World::new();
+        let vi = world.spawn(Champion::new(
+            Vi::NAME.to_string(),
+            6,
+            [1, 1, 3, 1],
+            Vi::new().get_name_closures(),
+        ));
+        let targets: Vec<EntityId> = [(100.0, 0.0), (50.0, 0.0), (150.0, 20.0), (-300.0, 0.0)]
+            .into_iter()
+            .map(|(x, y)| {
+                let mut target = Champion::new_dummy();
+                target.position = Position::new(x, y);
+                world.spawn(target)
+            })
+            .collect();
+        let damages =
+            world.execute_ability_on_targets(vi, &name, &targets, 0, &CastingData::new(0));
+        assert!(damages[0] > 0.0);
+        for (share, damage) in expected_share.iter().zip(&damages) {
+            assert!((share * damages[0] - damage).abs() < 1e-9);
+        }
+    }
+
+    #[rstest]
+    fn test_ult_knocks_up_through_tenacity() {
+        let mut world = World::new();
+        let vi = world.spawn(vi_at_origin());
+        let mut target = Champion::new_dummy();
+        apply_item_to_champ("Mercury's Treads", &mut target);
+        let target = world.spawn(target);
+        world.execute_ability(vi, &AbilityName::R, target, &CastingData::default());
+        assert!(world[target].is_crowd_controlled());
+        assert_relative_eq!(1.3, world[target].crowd_control_remaining());
+
+        // a knocked up champion can't hit back
+        let damage = world.execute_ability(target, &AbilityName::AUTO, vi, &CastingData::default());
+        assert_eq!(0.0, damage);
+        world.advance_time(1.4);
+        assert!(!world[target].is_crowd_controlled());
+    }
+
+    #[rstest]
+    fn test_full_combo_with_summoners() {
+        let level = 6;
+
+        let mut vi_data = Vi::new();
+        let vi_closures = vi_data.get_name_closures();
+        let mut vi = Champion::new(Vi::NAME.to_string(), level, [1, 1, 3, 1], vi_closures);
+
+        vi.stats.bonus_attack_damage += 40.0;
+        let combo = Vi::ult_combo_with_summoners(&[SummonerSpell::Flash, SummonerSpell::Ignite]);
+        assert_eq!(AbilityName::Summoner(SummonerSpell::Flash), combo[0].0);
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
+        world.execute_combo(vi, combo, target);
+        assert_eq!(965, world[target].get_missing_health().round() as u32);
+        world.advance_time(5.0);
+        // ult combo plus 50 + 20 * level ignite
+        assert_eq!(965 + 170, world[target].get_missing_health().round() as u32);
+    }
+
     #[rstest]
     fn test_full_combo_2() {
         let level = 6;
 
         let mut vi_data = Vi::new();
         let vi_closures = vi_data.get_name_closures();
-        let mut vi = Champion::new(Vi::NAME.to_string(), level, [0, 0, 2, 0], vi_closures);
+        let mut vi = Champion::new(Vi::NAME.to_string(), level, [1, 1, 3, 1], vi_closures);
 
         let item_names = ["Serrated Dirk", "Last Whisper"];
         for item_name in &item_names {
             apply_item_to_champ(item_name, &mut vi);
         }
 
-        let target = &mut Champion::new_dummy_with_resist(30.0, 0.0);
-        let ranks = vi.ranks;
-        Champion::execute_combo(Rc::new(RefCell::new(vi)), Vi::ult_combo(ranks), target);
+        let mut world = World::new();
+        let target = world.spawn(Champion::new_dummy_with_resist(30.0, 0.0));
+        let vi = world.spawn(vi);
+        world.execute_combo(vi, Vi::ult_combo(), target);
 
-        assert_eq!(905, target.get_missing_health().round() as u32);
+        assert_eq!(905, world[target].get_missing_health().round() as u32);
     }
 
     #[rstest]
     fn test_w_via_autos() {
-        let target = &mut Champion::new_dummy_with_resist(30.0, 0.0);
+        let mut world = World::new();
+        let target = world.spawn(Champion::new_dummy_with_resist(30.0, 0.0));
 
         let mut vi_data = Vi::new();
         let vi_closures = vi_data.get_name_closures();
-        let vi = Rc::new(RefCell::new(Champion::new(
+        let vi = world.spawn(Champion::new(
             Vi::NAME.to_string(),
             6,
-            [0, 0, 2, 0],
+            [1, 1, 3, 1],
             vi_closures,
-        )));
+        ));
 
         const HITS: usize = 9;
         let mut missing_healths: [f64; HITS] = [0.0; HITS];
-        for i in 0..HITS {
-            Champion::execute_ability(
-                Rc::downgrade(&vi),
+        for missing_health in missing_healths.iter_mut() {
+            world.execute_ability(
+                vi,
                 &AbilityName::AUTO,
                 target,
                 &CastingData {
@@ -344,7 +749,7 @@ mod tests {
             );
             TIME.with(|time| *time.borrow_mut() += 1.0);
 
-            missing_healths[i] = target.get_missing_health();
+            *missing_health = world[target].get_missing_health();
         }
 
         let mut damage: [f64; HITS] = [0.0; HITS];
@@ -378,4 +783,100 @@ mod tests {
             damage
         );
     }
+
+    fn vi_at_origin() -> Champion {
+        return Champion::new(
+            Vi::NAME.to_string(),
+            6,
+            [1, 1, 1, 1],
+            Vi::new().get_name_closures(),
+        );
+    }
+
+    fn vi_and_dummy_at(x: f64) -> (World, EntityId, EntityId) {
+        let mut world = World::new();
+        let vi = world.spawn(vi_at_origin());
+        let mut target = Champion::new_dummy();
+        target.position = Position::new(x, 0.0);
+        let target = world.spawn(target);
+        return (world, vi, target);
+    }
+
+    #[rstest]
+    #[case(0.0, false)]
+    #[case(0.5, true)]
+    #[case(Vi::Q_MAX_DAMAGE_CHARGE, true)]
+    fn test_q_range_scales_with_charge(#[case] charge: f64, #[case] reaches: bool) {
+        let (mut world, vi, target) = vi_and_dummy_at(400.0);
+        let damage = world.execute_ability(
+            vi,
+            &AbilityName::Q,
+            target,
+            &CastingData {
+                charge,
+                ..Default::default()
+            },
+        );
+        assert_eq!(reaches, damage > 0.0);
+        // the dash ends on the target
+        let expected_position = if reaches {
+            world[target].position
+        } else {
+            Position::default()
+        };
+        assert_eq!(expected_position, world[vi].position);
+    }
+
+    #[rstest]
+    fn test_combo_walks_into_range() {
+        TIME.with(|time| *time.borrow_mut() = 0.0);
+        let (mut world, vi, target) = vi_and_dummy_at(1000.0);
+        world.execute_combo(
+            vi,
+            vec![(AbilityName::AUTO, CastingData::default())],
+            target,
+        );
+        assert!(world[target].get_missing_health() > 0.0);
+        let walked = 1000.0 - world[vi].stats.attack_range;
+        assert!((walked - world[vi].position.x).abs() < 1e-9);
+        let elapsed = TIME.with(|time| *time.borrow());
+        assert!((walked / world[vi].get_move_speed() - elapsed).abs() < 1e-9);
+    }
+
+    #[rstest]
+    fn test_rooted_cannot_walk_into_range() {
+        let (mut world, vi, target) = vi_and_dummy_at(1000.0);
+        world[vi].apply_crowd_control("Root", CrowdControl::Root, 2.0);
+        assert_eq!(
+            None,
+            world[vi].time_to_reach(
+                &AbilityName::AUTO,
+                &CastingData::default(),
+                &world[target].position
+            )
+        );
+        world.execute_combo(
+            vi,
+            vec![(AbilityName::AUTO, CastingData::default())],
+            target,
+        );
+        assert_eq!(0.0, world[target].get_missing_health());
+        assert_eq!(Position::default(), world[vi].position);
+    }
+
+    #[rstest]
+    fn test_w_capped_against_monsters() {
+        let mut world = World::new();
+        let vi = world.spawn(Champion::new(
+            Vi::NAME.to_string(),
+            18,
+            [0, 5, 0, 0],
+            Vi::new().get_name_closures(),
+        ));
+        let baron = world.spawn(Champion::new_monster(MonsterKind::BaronNashor, 18));
+        let damage = world.execute_ability(vi, &AbilityName::WPassive, baron, &CastingData::new(4));
+        let expected =
+            world[baron].mitigated_damage(&world[vi], Vi::W_MONSTER_CAP, DamageType::Physical);
+        assert!((expected - damage).abs() < 1e-9);
+    }
 }
@@ -22,6 +22,13 @@ pub fn stat_at_level(base: f64, growth: f64, level: u8) -> f64 {
     return base + growth * (level as f64 - 1.0) * (0.7025 + 0.0175 * (level as f64 - 1.0));
 }
 
+/**
+ * Linear growth from level 1 to 18, used by most runes (ie electrocute's 30 - 220)
+ */
+pub fn linear_by_level(start: f64, end: f64, level: u8) -> f64 {
+    return start + (end - start) * (level.clamp(1, 18) as f64 - 1.0) / 17.0;
+}
+
 pub fn haste_to_cdr(mut haste: f64) -> f64 {
     if haste > 500.0 {
         haste = 500.0;
@@ -59,6 +66,18 @@ mod tests {
         assert_relative_eq!(expected, stat_at_level(base, growth, level))
     }
     #[rstest]
+    #[case(30.0, 220.0, 1, 30.0)]
+    #[case(30.0, 220.0, 18, 220.0)]
+    #[case(0.0, 17.0, 6, 5.0)]
+    fn test_linear_by_level(
+        #[case] start: f64,
+        #[case] end: f64,
+        #[case] level: u8,
+        #[case] expected: f64,
+    ) {
+        assert_relative_eq!(expected, linear_by_level(start, end, level))
+    }
+    #[rstest]
     #[case(100.0, 50.0)]
     #[case(544.0, 250.0/3.0)] // test cdr past 500 cap
     fn test_haste_to_cdr(#[case] haste: f64, #[case] expected: f64) {
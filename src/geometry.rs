@@ -0,0 +1,225 @@
+use std::ops::{Add, Mul, Sub};
+
+use serde::{Deserialize, Serialize};
+
+/**
+ * Point on the map in game units
+ */
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Position {
+    pub fn new(x: f64, y: f64) -> Position {
+        Position { x, y }
+    }
+
+    pub fn length(&self) -> f64 {
+        return self.x.hypot(self.y);
+    }
+
+    pub fn distance(&self, other: &Position) -> f64 {
+        return (*other - *self).length();
+    }
+
+    pub fn dot(&self, other: &Position) -> f64 {
+        return self.x * other.x + self.y * other.y;
+    }
+
+    /**
+     * Point `distance` units along the way to `destination`, stopping on it
+     */
+    pub fn moved_towards(&self, destination: &Position, distance: f64) -> Position {
+        let offset = *destination - *self;
+        if offset.length() <= distance {
+            return *destination;
+        }
+        return *self + offset * (distance / offset.length());
+    }
+}
+
+impl Add for Position {
+    type Output = Position;
+    fn add(self, other: Position) -> Position {
+        Position::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Position {
+    type Output = Position;
+    fn sub(self, other: Position) -> Position {
+        Position::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Mul<f64> for Position {
+    type Output = Position;
+    fn mul(self, scale: f64) -> Position {
+        Position::new(self.x * scale, self.y * scale)
+    }
+}
+
+/**
+ * Area an ability hits, aimed from the caster at its primary target
+ */
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum TargetShape {
+    #[default]
+    Single,
+    Circle {
+        radius: f64, // around the primary target
+    },
+    Cone {
+        range: f64,
+        angle: f64, // full width in degrees
+    },
+    Line {
+        length: f64,
+        width: f64,
+    },
+    Path {
+        width: f64, // from the caster up to the primary target, ie a dash
+    },
+    AllInRange {
+        range: f64, // around the caster
+    },
+}
+
+impl TargetShape {
+    /**
+     * Whether a unit other than the primary target is hit
+     */
+    pub fn contains(&self, caster: &Position, primary: &Position, candidate: &Position) -> bool {
+        let offset = *candidate - *caster;
+        let aim = *primary - *caster;
+        // segment from the caster along the aim, any direction when the primary is on top of the caster
+        let within_segment = |length: f64, width: f64| {
+            if aim.length() == 0.0 {
+                return offset.length() <= width / 2.0;
+            }
+            let direction = aim * (1.0 / aim.length());
+            let along = offset.dot(&direction);
+            let across = (offset - direction * along).length();
+            return (0.0..=length).contains(&along) && across <= width / 2.0;
+        };
+        return match self {
+            TargetShape::Single => false,
+            TargetShape::Circle { radius } => candidate.distance(primary) <= *radius,
+            TargetShape::AllInRange { range } => offset.length() <= *range,
+            TargetShape::Cone { range, angle } => {
+                if offset.length() > *range {
+                    return false;
+                }
+                if aim.length() == 0.0 || offset.length() == 0.0 {
+                    return true;
+                }
+                let cos = offset.dot(&aim) / (offset.length() * aim.length());
+                return cos.clamp(-1.0, 1.0).acos().to_degrees() <= angle / 2.0;
+            }
+            TargetShape::Line { length, width } => within_segment(*length, *width),
+            TargetShape::Path { width } => within_segment(aim.length(), *width),
+        };
+    }
+}
+
+/**
+ * How far from its target an ability can be cast, center to center
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CastRange {
+    AttackRange, // empowered attacks, follows the caster's attack range
+    Fixed(f64),
+    Charged {
+        min: f64,
+        max: f64,
+        full_charge: f64, // seconds of charge to reach max
+    },
+}
+
+impl CastRange {
+    pub fn value(&self, attack_range: f64, charge: f64) -> f64 {
+        return match self {
+            CastRange::AttackRange => attack_range,
+            CastRange::Fixed(range) => *range,
+            CastRange::Charged {
+                min,
+                max,
+                full_charge,
+            } => min + (max - min) * (charge / full_charge).clamp(0.0, 1.0),
+        };
+    }
+}
+
+/**
+ * Seconds for a chaser to close `gap` units on a target running straight away, never if it is not faster
+ */
+pub fn time_to_close(gap: f64, chaser_speed: f64, fleeing_speed: f64) -> Option<f64> {
+    if gap <= 0.0 {
+        return Some(0.0);
+    }
+    if chaser_speed <= fleeing_speed {
+        return None;
+    }
+    return Some(gap / (chaser_speed - fleeing_speed));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(TargetShape::Single, Position::new(500.0, 0.0), false)]
+    #[case(TargetShape::Circle { radius: 200.0 }, Position::new(500.0, 150.0), true)]
+    #[case(TargetShape::Circle { radius: 200.0 }, Position::new(200.0, 0.0), false)]
+    #[case(TargetShape::AllInRange { range: 300.0 }, Position::new(0.0, -300.0), true)]
+    #[case(TargetShape::Cone { range: 600.0, angle: 90.0 }, Position::new(400.0, 300.0), true)]
+    #[case(TargetShape::Cone { range: 600.0, angle: 60.0 }, Position::new(400.0, 300.0), false)]
+    #[case(TargetShape::Cone { range: 600.0, angle: 90.0 }, Position::new(-100.0, 0.0), false)]
+    #[case(TargetShape::Line { length: 1000.0, width: 100.0 }, Position::new(900.0, 40.0), true)]
+    #[case(TargetShape::Line { length: 1000.0, width: 100.0 }, Position::new(900.0, 60.0), false)]
+    #[case(TargetShape::Path { width: 100.0 }, Position::new(250.0, 0.0), true)]
+    #[case(TargetShape::Path { width: 100.0 }, Position::new(700.0, 0.0), false)]
+    fn test_shape_contains(
+        #[case] shape: TargetShape,
+        #[case] candidate: Position,
+        #[case] expected: bool,
+    ) {
+        let caster = Position::default();
+        let primary = Position::new(500.0, 0.0);
+        assert_eq!(expected, shape.contains(&caster, &primary, &candidate));
+    }
+
+    #[rstest]
+    #[case(100.0, Position::new(60.0, 80.0))]
+    #[case(500.0, Position::new(300.0, 400.0))]
+    fn test_moved_towards(#[case] distance: f64, #[case] expected: Position) {
+        let destination = Position::new(300.0, 400.0);
+        let moved = Position::default().moved_towards(&destination, distance);
+        assert!(moved.distance(&expected) < 1e-9);
+    }
+
+    #[rstest]
+    #[case(CastRange::AttackRange, 0.0, 125.0)]
+    #[case(CastRange::Fixed(800.0), 1.0, 800.0)]
+    #[case(CastRange::Charged { min: 250.0, max: 750.0, full_charge: 1.0 }, 0.5, 500.0)]
+    #[case(CastRange::Charged { min: 250.0, max: 750.0, full_charge: 1.0 }, 3.0, 750.0)]
+    fn test_cast_range(#[case] range: CastRange, #[case] charge: f64, #[case] expected: f64) {
+        assert_eq!(expected, range.value(125.0, charge));
+    }
+
+    #[rstest]
+    #[case(-10.0, 300.0, 400.0, Some(0.0))]
+    #[case(100.0, 350.0, 300.0, Some(2.0))]
+    #[case(100.0, 340.0, 340.0, None)]
+    fn test_time_to_close(
+        #[case] gap: f64,
+        #[case] chaser_speed: f64,
+        #[case] fleeing_speed: f64,
+        #[case] expected: Option<f64>,
+    ) {
+        assert_eq!(expected, time_to_close(gap, chaser_speed, fleeing_speed));
+    }
+}
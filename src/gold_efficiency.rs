@@ -0,0 +1,297 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    item_effects::ConcreteItemEffect,
+    load_champion::{ChampionStatModifier, ChampionStats},
+    load_wiki_item::{
+        load_wiki_item_cost, load_wiki_item_effects, load_wiki_item_names, load_wiki_item_stats,
+        load_wiki_item_types,
+    },
+};
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum PricedStat {
+    AttackDamage,
+    AbilityPower,
+    Armor,
+    MagicResist,
+    Health,
+    Mana,
+    AttackSpeed,
+    CritChance,
+    Lethality,
+    PercentArmorPen,
+    FlatMagicPen,
+    PercentMagicPen,
+    AbilityHaste,
+    LifeSteal,
+    Omnivamp,
+    MoveSpeed,
+    PercentMoveSpeed,
+    BaseHealthRegen, // percent of base health regen, the wiki's hp5
+    BaseManaRegen,   // percent of base mana regen, the wiki's mp5
+    HealthRegen,     // flat health per 5 seconds, the wiki's hp5flat
+}
+
+impl PricedStat {
+    pub const ALL: [PricedStat; 20] = [
+        PricedStat::AttackDamage,
+        PricedStat::AbilityPower,
+        PricedStat::Armor,
+        PricedStat::MagicResist,
+        PricedStat::Health,
+        PricedStat::Mana,
+        PricedStat::AttackSpeed,
+        PricedStat::CritChance,
+        PricedStat::Lethality,
+        PricedStat::PercentArmorPen,
+        PricedStat::FlatMagicPen,
+        PricedStat::PercentMagicPen,
+        PricedStat::AbilityHaste,
+        PricedStat::LifeSteal,
+        PricedStat::Omnivamp,
+        PricedStat::MoveSpeed,
+        PricedStat::PercentMoveSpeed,
+        PricedStat::BaseHealthRegen,
+        PricedStat::BaseManaRegen,
+        PricedStat::HealthRegen,
+    ];
+
+    /**
+     * Amount of this stat in the given stats, meant to be called on the deltas an item grants
+     */
+    pub fn amount(&self, stats: &ChampionStats) -> f64 {
+        return match self {
+            PricedStat::AttackDamage => stats.bonus_attack_damage,
+            PricedStat::AbilityPower => stats.ability_power,
+            PricedStat::Armor => stats.armor,
+            PricedStat::MagicResist => stats.magic_resist,
+            PricedStat::Health => stats.health,
+            PricedStat::Mana => stats.mana,
+            PricedStat::AttackSpeed => stats.bonus_attack_speed,
+            PricedStat::CritChance => stats.critical_strike_chance,
+            PricedStat::Lethality => stats.lethality,
+            PricedStat::PercentArmorPen => stats.percent_armor_pen,
+            PricedStat::FlatMagicPen => stats.flat_magic_pen,
+            PricedStat::PercentMagicPen => stats.percent_magic_pen,
+            PricedStat::AbilityHaste => stats.ability_haste,
+            PricedStat::LifeSteal => stats.life_steal,
+            PricedStat::Omnivamp => stats.omnivamp,
+            PricedStat::MoveSpeed => stats.move_speed,
+            PricedStat::PercentMoveSpeed => stats.percent_movement_speed,
+            PricedStat::BaseHealthRegen => stats.percent_base_health_regen,
+            PricedStat::BaseManaRegen => stats.percent_base_mana_regen,
+            PricedStat::HealthRegen => stats.health_regen,
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StatPrice {
+    pub gold_per_point: f64,
+    pub source_item: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct StatPrices {
+    prices: HashMap<PricedStat, StatPrice>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoldEfficiency {
+    pub item_name: String,
+    pub cost: f64,
+    pub gold_value: f64,
+    pub unpriced_stats: Vec<PricedStat>,
+}
+
+impl GoldEfficiency {
+    /**
+     * Gold value of the stats divided by the cost, 1.0 is exactly gold efficient
+     */
+    pub fn efficiency(&self) -> f64 {
+        return self.gold_value / self.cost;
+    }
+}
+
+/**
+ * Stats the item grants, including passives we model as flat stats (ie gouge's lethality)
+ */
+pub fn item_stat_deltas(item_name: &str) -> ChampionStats {
+    let mut stats = ChampionStats::default();
+    load_wiki_item_stats(item_name).modify_champion_stats(&mut stats);
+    concrete_item_effects(item_name).iter().for_each(|effect| {
+        if let ConcreteItemEffect::StatItemEffect(v) = effect {
+            v.stats.modify_champion_stats(&mut stats)
+        }
+    });
+    return stats;
+}
+
+fn concrete_item_effects(item_name: &str) -> Vec<ConcreteItemEffect> {
+    return load_wiki_item_effects(item_name)
+        .iter()
+        .map(|effect| (effect, item_name).into())
+        .collect();
+}
+
+fn has_only_stat_effects(item_name: &str) -> bool {
+    return concrete_item_effects(item_name)
+        .iter()
+        .all(|effect| matches!(effect, ConcreteItemEffect::StatItemEffect(_)));
+}
+
+impl StatPrices {
+    /**
+     * Prices each stat off the cheapest basic item that provides it (Long Sword for ad, Cloth Armor for armor, ...).
+     * Stats no basic item provides are priced off the cheapest item whose only unpriced stat is that one,
+     * eg lethality off serrated dirk, so long as the item has no passives we don't model as stats
+     */
+    pub fn from_wiki_items() -> StatPrices {
+        let mut candidates: Vec<(String, f64, ChampionStats, bool)> = load_wiki_item_names()
+            .iter()
+            .filter_map(|name| {
+                let cost = load_wiki_item_cost(name).filter(|cost| *cost > 0.0)?;
+                if !has_only_stat_effects(name) {
+                    return None;
+                }
+                let is_basic = load_wiki_item_types(name)
+                    .iter()
+                    .any(|item_type| item_type == "Basic");
+                let stats = item_stat_deltas(name);
+                return Some((name.clone(), cost, stats, is_basic));
+            })
+            .collect();
+        candidates.sort_by(|l, r| l.1.total_cmp(&r.1).then_with(|| l.0.cmp(&r.0)));
+
+        let mut out = StatPrices::default();
+        for (name, cost, stats, _) in candidates.iter().filter(|candidate| candidate.3) {
+            let provided: Vec<PricedStat> = PricedStat::ALL
+                .into_iter()
+                .filter(|stat| stat.amount(stats) > 0.0)
+                .collect();
+            if let [stat] = provided[..] {
+                out.prices.entry(stat).or_insert(StatPrice {
+                    gold_per_point: cost / stat.amount(stats),
+                    source_item: name.clone(),
+                });
+            }
+        }
+
+        while let Some((stat, price)) = candidates.iter().find_map(|(name, cost, stats, _)| {
+            let unpriced = out.unpriced_stats(stats);
+            if let [stat] = unpriced[..] {
+                let remaining = cost - out.gold_value(stats);
+                if remaining > 0.0 {
+                    return Some((
+                        stat,
+                        StatPrice {
+                            gold_per_point: remaining / stat.amount(stats),
+                            source_item: name.clone(),
+                        },
+                    ));
+                }
+            }
+            return None;
+        }) {
+            out.prices.insert(stat, price);
+        }
+        return out;
+    }
+
+    pub fn get(&self, stat: &PricedStat) -> Option<&StatPrice> {
+        self.prices.get(stat)
+    }
+
+    /**
+     * Gold value of the priced stats, unpriced stats contribute nothing
+     */
+    pub fn gold_value(&self, stats: &ChampionStats) -> f64 {
+        return self
+            .prices
+            .iter()
+            .map(|(stat, price)| stat.amount(stats) * price.gold_per_point)
+            .sum();
+    }
+
+    pub fn unpriced_stats(&self, stats: &ChampionStats) -> Vec<PricedStat> {
+        return PricedStat::ALL
+            .into_iter()
+            .filter(|stat| stat.amount(stats) > 0.0 && !self.prices.contains_key(stat))
+            .collect();
+    }
+
+    pub fn item_gold_efficiency(&self, item_name: &str) -> Option<GoldEfficiency> {
+        let cost = load_wiki_item_cost(item_name).filter(|cost| *cost > 0.0)?;
+        let stats = item_stat_deltas(item_name);
+        return Some(GoldEfficiency {
+            item_name: item_name.to_string(),
+            cost,
+            gold_value: self.gold_value(&stats),
+            unpriced_stats: self.unpriced_stats(&stats),
+        });
+    }
+}
+
+static STAT_PRICES: Lazy<StatPrices> = Lazy::new(StatPrices::from_wiki_items);
+
+pub fn load_stat_prices() -> &'static StatPrices {
+    &STAT_PRICES
+}
+
+pub fn item_gold_efficiency(item_name: &str) -> Option<GoldEfficiency> {
+    load_stat_prices().item_gold_efficiency(item_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(PricedStat::AttackDamage, "Long Sword", 35.0)]
+    #[case(PricedStat::Armor, "Cloth Armor", 20.0)]
+    #[case(PricedStat::AbilityPower, "Amplifying Tome", 21.75)]
+    #[case(PricedStat::Health, "Ruby Crystal", 400.0 / 150.0)]
+    // no basic item grants these, derived from epic items
+    #[case(PricedStat::AbilityHaste, "Kindlegem", 80.0 / 3.0)]
+    #[case(PricedStat::Lethality, "Serrated Dirk", 5.0)]
+    fn test_stat_prices(
+        #[case] stat: PricedStat,
+        #[case] expected_source: &str,
+        #[case] expected_price: f64,
+    ) {
+        let prices = load_stat_prices();
+        let price = prices.get(&stat).unwrap();
+        assert_eq!(expected_source, price.source_item);
+        assert_relative_eq!(expected_price, price.gold_per_point, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    #[case("Long Sword", 1.0)]
+    #[case("Pickaxe", 1.0)]
+    #[case("Serrated Dirk", 1.0)] // gouge lethality is counted
+    #[case("Caulfield's Warhammer", 875.0 / 1100.0 + 10.0 * (80.0 / 3.0) / 1100.0)]
+    fn test_item_gold_efficiency(#[case] item_name: &str, #[case] expected: f64) {
+        let efficiency = item_gold_efficiency(item_name).unwrap();
+        assert!(efficiency.unpriced_stats.is_empty());
+        assert_relative_eq!(expected, efficiency.efficiency(), epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_regen_units() {
+        // rejuvenation bead's hp5 is 100% base health regen, not 100 health per 5 seconds
+        let bead = item_stat_deltas("Rejuvenation Bead");
+        assert_eq!(100.0, PricedStat::BaseHealthRegen.amount(&bead));
+        assert_eq!(0.0, PricedStat::HealthRegen.amount(&bead));
+        // doran's shield grants flat regen, only sold alongside passives so it stays unpriced
+        let shield = item_stat_deltas("Doran's Shield");
+        assert_eq!(4.0, PricedStat::HealthRegen.amount(&shield));
+        assert_eq!(0.0, PricedStat::BaseHealthRegen.amount(&shield));
+        let efficiency = item_gold_efficiency("Doran's Shield").unwrap();
+        assert_eq!(vec![PricedStat::HealthRegen], efficiency.unpriced_stats);
+    }
+}
@@ -0,0 +1,209 @@
+use serde::Deserialize;
+
+use crate::{
+    champions::champion::{AbilityFn, AbilityName, CastingData},
+    core::haste_to_cdr,
+    item_effects::WikiDamage,
+    load_wiki_item::load_wiki_item_active,
+    target::{EffectData, EffectResult},
+    time_manager::TIME,
+    world::{EntityId, World},
+};
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, Deserialize)]
+pub enum ItemActive {
+    Goredrinker,
+    Stridebreaker,
+    ProwlersClaw,
+    IronspikeWhip,
+    Everfrost,
+    HextechRocketbelt,
+}
+
+impl ItemActive {
+    pub const ALL: [ItemActive; 6] = [
+        ItemActive::Goredrinker,
+        ItemActive::Stridebreaker,
+        ItemActive::ProwlersClaw,
+        ItemActive::IronspikeWhip,
+        ItemActive::Everfrost,
+        ItemActive::HextechRocketbelt,
+    ];
+
+    pub fn item_name(&self) -> &'static str {
+        return match self {
+            ItemActive::Goredrinker => "Goredrinker",
+            ItemActive::Stridebreaker => "Stridebreaker",
+            ItemActive::ProwlersClaw => "Prowler's Claw",
+            ItemActive::IronspikeWhip => "Ironspike Whip",
+            ItemActive::Everfrost => "Everfrost",
+            ItemActive::HextechRocketbelt => "Hextech Rocketbelt",
+        };
+    }
+
+    /**
+     * Distance covered by actives that dash towards the target
+     */
+    fn dash_distance(&self) -> Option<f64> {
+        return match self {
+            ItemActive::ProwlersClaw => Some(500.0),
+            ItemActive::HextechRocketbelt => Some(275.0),
+            _ => None,
+        };
+    }
+}
+
+/**
+ * Item active whose damage and cooldown are read from its wiki description.
+ * Casting does nothing unless the caster holds the item and it is off cooldown
+ */
+fn item_active(active: ItemActive) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+    let effect = load_wiki_item_active(active.item_name())
+        .unwrap_or_else(|| panic!("{} should have an active", active.item_name()));
+    let damage = WikiDamage::from_effect(&effect);
+    let cooldown = effect
+        .cooldown()
+        .unwrap_or_else(|| panic!("{} should have a cooldown", effect.name));
+    let reduced_by_haste = effect
+        .cd
+        .as_deref()
+        .is_some_and(|cd| cd.contains("ability haste"));
+    let name = effect.name;
+    return move |world: &mut World,
+                 attacker_id: EntityId,
+                 target_id: EntityId,
+                 _casting_data: &CastingData| {
+        let destination = world[target_id].position;
+        {
+            let attacker = &mut world[attacker_id];
+            if !attacker.items.iter().any(|item| item == active.item_name())
+                || attacker.is_on_cooldown(&name)
+            {
+                return;
+            }
+            let mut active_cooldown = cooldown.value(attacker.is_ranged(), attacker.level);
+            if reduced_by_haste {
+                active_cooldown *= 1.0 - haste_to_cdr(attacker.stats.ability_haste) / 100.0;
+            }
+            attacker.put_on_cooldown(&name, active_cooldown);
+            if let Some(distance) = active.dash_distance() {
+                attacker.dash_towards(&destination, distance);
+            }
+        }
+
+        damage.deal(world, attacker_id, target_id);
+        match active {
+            ItemActive::Goredrinker => {
+                let attacker = &mut world[attacker_id];
+                let sheet = attacker.stat_sheet();
+                let heal = 0.2 * sheet.attack_damage.total()
+                    + 0.08 * (sheet.health.total() - attacker.current_health);
+                attacker.heal(heal);
+            }
+            ItemActive::ProwlersClaw => {
+                world[target_id].upsert_effect(EffectData {
+                    unique_name: name.clone(),
+                    expiry: TIME.with(|time| *time.borrow() + 3.0),
+                    result: EffectResult::IncomingDamageAmplifier(15.0),
+                });
+            }
+            _ => {}
+        }
+    };
+}
+
+pub fn item_active_abilities() -> Vec<(AbilityName, Box<AbilityFn>)> {
+    return ItemActive::ALL
+        .into_iter()
+        .map(|active| {
+            let func: Box<AbilityFn> = Box::new(item_active(active));
+            (AbilityName::ItemActive(active), func)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{champions::champion::Champion, load_wiki_item::apply_item_to_champ};
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    fn cast(world: &mut World, attacker: EntityId, name: AbilityName, target: EntityId) -> f64 {
+        world.execute_ability(attacker, &name, target, &CastingData::default())
+    }
+
+    fn attacker_with(item_name: Option<&str>) -> (World, EntityId, EntityId) {
+        let mut attacker = Champion::new_dummy();
+        attacker.stats.base_attack_damage = 100.0;
+        if let Some(item_name) = item_name {
+            apply_item_to_champ(item_name, &mut attacker);
+        }
+        let mut world = World::new();
+        let attacker = world.spawn(attacker);
+        let target = world.spawn(Champion::new_dummy());
+        (world, attacker, target)
+    }
+
+    #[rstest]
+    #[case(ItemActive::Goredrinker, |attacker: &Champion| 1.75 * attacker.get_base_ad())]
+    #[case(ItemActive::Stridebreaker, |attacker: &Champion| 1.75 * attacker.get_base_ad())]
+    #[case(ItemActive::IronspikeWhip, |attacker: &Champion| attacker.get_base_ad())]
+    #[case(ItemActive::ProwlersClaw, |attacker: &Champion| 75.0 + 0.3 * attacker.get_bonus_ad())]
+    #[case(ItemActive::Everfrost, |attacker: &Champion| 100.0 + 0.3 * attacker.get_ability_power())]
+    fn test_item_active_damage_and_cooldown(
+        #[case] active: ItemActive,
+        #[case] expected: fn(&Champion) -> f64,
+    ) {
+        let (mut world, attacker, target) = attacker_with(Some(active.item_name()));
+        let damage = cast(
+            &mut world,
+            attacker,
+            AbilityName::ItemActive(active),
+            target,
+        );
+        assert_relative_eq!(expected(&world[attacker]), damage, epsilon = 1e-9);
+
+        let recast = cast(
+            &mut world,
+            attacker,
+            AbilityName::ItemActive(active),
+            target,
+        );
+        assert_relative_eq!(0.0, recast);
+    }
+
+    #[rstest]
+    fn test_item_active_requires_item() {
+        let (mut world, attacker, target) = attacker_with(None);
+        let goredrinker = AbilityName::ItemActive(ItemActive::Goredrinker);
+        let damage = cast(&mut world, attacker, goredrinker, target);
+        assert_relative_eq!(0.0, damage);
+    }
+
+    #[rstest]
+    fn test_item_active_arms_spellblade() {
+        let (mut world, attacker, target) = attacker_with(Some("Goredrinker"));
+        apply_item_to_champ("Sheen", &mut world[attacker]);
+        let plain_auto = cast(&mut world, attacker, AbilityName::AUTO, target);
+        let goredrinker = AbilityName::ItemActive(ItemActive::Goredrinker);
+        cast(&mut world, attacker, goredrinker, target);
+        let empowered_auto = cast(&mut world, attacker, AbilityName::AUTO, target);
+        assert_relative_eq!(
+            world[attacker].get_base_ad(),
+            empowered_auto - plain_auto,
+            epsilon = 1e-9
+        );
+    }
+
+    #[rstest]
+    // the whip leaves the dummy at 40 health, below the collector's 5% of 1000
+    fn test_item_active_executes() {
+        let (mut world, attacker, target) = attacker_with(Some("Ironspike Whip"));
+        apply_item_to_champ("The Collector", &mut world[attacker]);
+        world[target].current_health = 140.0;
+        let whip = AbilityName::ItemActive(ItemActive::IronspikeWhip);
+        cast(&mut world, attacker, whip, target);
+        assert_relative_eq!(0.0, world[target].current_health);
+    }
+}
@@ -1,91 +1,348 @@
-use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::Debug;
-use std::rc::{Rc, Weak};
 
 use crate::attack::BasicAttack;
-use crate::champions::champion::{AbilityName, CastingData, Champion};
-use crate::target::{AbilityEffect, EffectData, EffectResult, EmpowerState, VitalityData};
+use crate::champions::champion::{AbilityFn, AbilityName, CastingData, Champion};
+use crate::item_actives::item_active_abilities;
+use crate::runes::rune_abilities;
+use crate::stat_sheet::HitSheets;
+use crate::summoner_spells::summoner_abilities;
+use crate::target::{
+    AbilityEffect, CrowdControl, DamageType, EffectData, EffectResult, EmpowerState, Target,
+    VitalityData,
+};
 use crate::time_manager::TIME;
-use crate::{load_champion::ChampionStatModifier, load_wiki_item::WikiItemStatDeltas};
+use crate::units::UnitKind;
+use crate::wiki_template::{
+    evaluate_terms, parse_cooldown, parse_damage_terms, parse_damage_type, parse_terms,
+    ScalingStat, ScalingValues, WikiQuantity, WikiTerm,
+};
+use crate::world::{EntityId, World};
+use crate::{
+    load_champion::ChampionStatModifier,
+    load_wiki_item::{load_wiki_item_effect, WikiItemStatDeltas},
+};
 use once_cell::sync::Lazy;
 use serde::Deserialize;
 
-fn get_auto_attack_ability() -> impl Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) -> () {
-    return move |target: &mut Champion,
-                 attacker: Rc<RefCell<Champion>>,
+fn get_auto_attack_ability() -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+    return move |world: &mut World,
+                 attacker: EntityId,
+                 target: EntityId,
                  _casting_data: &CastingData| {
-        let bonus_ad = attacker.borrow().get_bonus_ad();
-        let base_ad = attacker.borrow().get_base_ad();
+        let sheets = HitSheets::new(&world[attacker], &world[target]);
+        let attack = BasicAttack::from(&sheets.attacker);
 
-        let attack = BasicAttack::new(base_ad, bonus_ad);
-
-        let raw_damage = attack.get_damage_to_target(
-            &VitalityData::default(),
-            &attacker.borrow().crit_info,
-            None,
-        );
-        target.receive_damage(&attacker.borrow(), raw_damage);
+        let raw_damage =
+            attack.get_damage_to_target(&VitalityData::default(), &world[attacker].crit_info, None);
+        world.deal_typed_damage_with(attacker, target, &sheets, raw_damage, DamageType::Physical);
     };
 }
 
-thread_local! {
-    pub static STATIC_ABILITIES: Lazy<
-            HashMap<AbilityName, Box<dyn Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) -> ()>>
-        > = Lazy::new(|| {
-            let mut m: HashMap<
-                    AbilityName,
-                    Box<dyn Fn(&mut Champion, Rc<RefCell<Champion>>, &CastingData) -> ()>,
-                > = HashMap::new();
-            let nightstalker = move
-                |target: &mut Champion, attacker: Rc<RefCell<Champion>>, _casting_data: &CastingData| {
-                    let is_ranged = attacker.borrow().is_ranged();
-                    let bonus_ad = attacker.borrow().get_bonus_ad();
-
-                    let bonus_scaling = if is_ranged { 0.30 } else { 0.25 };
-                    let flat_damage = if is_ranged { 75.0 } else { 55.0 };
-                    target.receive_damage(&attacker.borrow(), flat_damage + bonus_ad * bonus_scaling);
-            };
-            let spellblade_sheen = move
-                |target: &mut Champion, attacker: Rc<RefCell<Champion>>, _casting_data: &CastingData| {
-                    let base_ad = attacker.borrow().get_base_ad();
-                    target.receive_damage(&attacker.borrow(), base_ad);
-            };
-            let spellblade_essence_reaver = move
-                |target: &mut Champion, attacker: Rc<RefCell<Champion>>, _casting_data: &CastingData| {
-                    let base_ad = attacker.borrow().get_base_ad();
-                    let bonus_ad = attacker.borrow().get_bonus_ad();
-                    target.receive_damage(&attacker.borrow(), base_ad + bonus_ad * 0.40);
-            };
-            let spellblade_divine_sunderer = move
-                |target: &mut Champion, attacker: Rc<RefCell<Champion>>, _casting_data: &CastingData| {
-                    let base_ad = attacker.borrow().get_base_ad();
-                    let target_max_health = target.get_max_health();
-                    let is_ranged = attacker.borrow().is_ranged();
-                    let percent_health_damage = if is_ranged { 0.06 } else { 0.03 };
-                    target.receive_damage(&attacker.borrow(), base_ad * 1.25 +  target_max_health * percent_health_damage);
+/**
+ * Spellblade holders and the ability each one's empowered attack casts
+ */
+pub const SPELLBLADES: [(&str, AbilityName); 6] = [
+    ("Sheen", AbilityName::SpellbladeSheen),
+    ("Essence Reaver", AbilityName::SpellbladeEssenceReaver),
+    ("Divine Sunderer", AbilityName::SpellbladeDivineSunderer),
+    ("Trinity Force", AbilityName::SpellbladeTrinityForce),
+    ("Lich Bane", AbilityName::SpellbladeLichBane),
+    ("Iceborn Gauntlet", AbilityName::SpellbladeIcebornGauntlet),
+];
+
+const ICEBORN_GAUNTLET_FIELD: &str = "Iceborn Gauntlet Frost Field";
+
+/**
+ * Floor or cap of a damage clause, ie mist's edge's "maximum of 60 against minions and monsters"
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DamageBound {
+    pub term: WikiTerm,
+    pub against: String, // units the bound holds against, empty for every unit
+}
+
+impl DamageBound {
+    /**
+     * First term after the bold word, ie "'''minimum'''", scoped by the "against" that follows it
+     */
+    fn parse(description: &str, word: &str) -> Option<DamageBound> {
+        let phrase = format!("'''{}''' of", word);
+        let term = terms_after(description, &phrase).into_iter().next()?;
+        // up to the end of the sentence or the next bound
+        let rest = &description[description.find(&phrase)? + phrase.len()..];
+        let clause = rest.split('.').next().unwrap_or_default();
+        let clause = clause.split(" and a ").next().unwrap_or_default();
+        let against = match clause.split_once("against") {
+            Some((_, units)) if !units.contains("all units") => units.trim().to_string(),
+            _ => String::new(),
+        };
+        return Some(DamageBound { term, against });
+    }
+
+    pub fn holds_against(&self, kind: UnitKind) -> bool {
+        return self.against.is_empty()
+            || match kind {
+                UnitKind::Champion => self.against.contains("champion"),
+                UnitKind::Minion(_) => self.against.contains("minion"),
+                UnitKind::Monster(_) => self.against.contains("monster"),
+                UnitKind::Turret(_) => self.against.contains("turret"),
             };
-            let auto_attack = get_auto_attack_ability();
-            m.insert(AbilityName::NIGHTSTALKER,
-                     Box::new(nightstalker));
-            m.insert(AbilityName::SpellbladeSheen,
-                     Box::new(spellblade_sheen));
-            m.insert(AbilityName::SpellbladeEssenceReaver,
-                     Box::new(spellblade_essence_reaver));
-            m.insert(AbilityName::SpellbladeDivineSunderer,
-                     Box::new(spellblade_divine_sunderer));
-            m.insert(AbilityName::AUTO,
-                     Box::new(auto_attack));
-            return m;
+    }
+}
+
+/**
+ * Damage clause of a wiki effect along with its damage type and bounds
+ */
+pub struct WikiDamage {
+    pub terms: Vec<WikiTerm>,
+    pub damage_type: DamageType,
+    pub minimum: Option<DamageBound>,
+    pub maximum: Option<DamageBound>,
+}
+
+impl WikiDamage {
+    pub fn from_effect(effect: &UnknownItemEffect) -> WikiDamage {
+        let description = &effect.description;
+        // the bounds follow the damage clause, their numbers aren't part of it
+        let clause = description
+            .split("'''minimum'''")
+            .next()
+            .unwrap_or_default();
+        WikiDamage {
+            terms: parse_damage_terms(clause),
+            damage_type: parse_damage_type(description).unwrap_or(DamageType::Physical),
+            minimum: DamageBound::parse(description, "minimum"),
+            maximum: DamageBound::parse(description, "maximum"),
+        }
+    }
+
+    fn load(item_name: &str, effect_name: &str) -> WikiDamage {
+        let effect = load_wiki_item_effect(item_name, effect_name)
+            .unwrap_or_else(|| panic!("{} should have a {} effect", item_name, effect_name));
+        WikiDamage::from_effect(effect)
+    }
+
+    fn raw(&self, attacker: &Champion, target: &Champion, sheets: &HitSheets) -> f64 {
+        let scaling = scaling_values(attacker, target, sheets);
+        let bound = |bound: &Option<DamageBound>| {
+            bound
+                .as_ref()
+                .filter(|bound| bound.holds_against(target.unit_kind()))
+                .map(|bound| evaluate_terms(std::slice::from_ref(&bound.term), &scaling))
+        };
+        let mut damage = evaluate_terms(&self.terms, &scaling);
+        if let Some(minimum) = bound(&self.minimum) {
+            damage = damage.max(minimum);
+        }
+        if let Some(maximum) = bound(&self.maximum) {
+            damage = damage.min(maximum);
+        }
+        return damage;
+    }
+
+    pub fn mitigated(&self, attacker: &Champion, target: &Champion) -> f64 {
+        let sheets = HitSheets::new(attacker, target);
+        let damage = self.raw(attacker, target, &sheets);
+        target.mitigated_damage_with(attacker, &sheets, damage, self.damage_type)
+    }
+
+    /**
+     * Deals the damage through the world, so execute thresholds apply
+     */
+    pub fn deal(&self, world: &mut World, attacker: EntityId, target: EntityId) {
+        let sheets = HitSheets::new(&world[attacker], &world[target]);
+        let damage = self.raw(&world[attacker], &world[target], &sheets);
+        world.deal_typed_damage_with(attacker, target, &sheets, damage, self.damage_type);
+    }
+}
+
+/**
+ * Iceborn gauntlet's frost field on the primary target, read from its spellblade description
+ */
+struct FrostField {
+    slow: WikiQuantity,                // percent
+    slow_per_100_health: WikiQuantity, // percent per 100 maximum health of the holder
+    primary_multiplier: f64,           // the primary target is slowed for "double the amount"
+    duration: f64,
+    damage_reduction: f64, // percent less damage the primary target deals to the holder
+}
+
+impl FrostField {
+    const MAX_SLOW: f64 = 99.0; // like nightstalker, a slow alone never stops the target
+
+    fn from_effect(effect: &UnknownItemEffect) -> Option<FrostField> {
+        let description = &effect.description;
+        let mut slow_terms = terms_after(description, "slowed by").into_iter();
+        let slow = slow_terms.next()?.quantity;
+        let slow_per_100_health = slow_terms
+            .next()
+            .filter(|term| term.following_text.contains("per"))?
+            .quantity;
+        return Some(FrostField {
+            slow,
+            slow_per_100_health,
+            primary_multiplier: match description.contains("double the amount") {
+                true => 2.0,
+                false => 1.0,
+            },
+            duration: value_after(description, "frost field for")?.value(false, 1),
+            damage_reduction: value_after(description, "reduced by")?.value(false, 1),
         });
+    }
+
+    fn primary_slow(&self, holder: &Champion) -> f64 {
+        let (is_ranged, level) = (holder.is_ranged(), holder.level);
+        let slow = self.slow.value(is_ranged, level)
+            + self.slow_per_100_health.value(is_ranged, level) * holder.get_max_health() / 100.0;
+        return (self.primary_multiplier * slow).min(FrostField::MAX_SLOW);
+    }
+}
+
+/**
+ * Item passive whose damage is read straight from its wiki description
+ */
+fn get_wiki_damage_ability(
+    item_name: &str,
+    effect_name: &str,
+) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+    let damage = WikiDamage::load(item_name, effect_name);
+    let frost_field = (item_name == "Iceborn Gauntlet").then(|| {
+        let effect = load_wiki_item_effect(item_name, effect_name).unwrap();
+        FrostField::from_effect(effect).expect("iceborn gauntlet should have a frost field")
+    });
+    let nightstalker_slow = (effect_name == "Nightstalker").then(|| {
+        let effect = load_wiki_item_effect(item_name, effect_name).unwrap();
+        parse_slow(&effect.description, "the target by").expect("nightstalker should slow")
+    });
+    return move |world: &mut World,
+                 attacker: EntityId,
+                 target: EntityId,
+                 _casting_data: &CastingData| {
+        damage.deal(world, attacker, target);
+        // duskblade's limit: "ranged champions do not apply the slow"
+        if let Some((slow, duration)) = nightstalker_slow.filter(|_| !world[attacker].is_ranged()) {
+            world[target].apply_crowd_control(
+                "Nightstalker Slow",
+                CrowdControl::Slow(slow),
+                duration,
+            );
+        }
+        if let Some(field) = &frost_field {
+            let slow = field.primary_slow(&world[attacker]);
+            let target = &mut world[target];
+            target.apply_crowd_control(
+                "Iceborn Gauntlet Frost Field Slow",
+                CrowdControl::Slow(slow),
+                field.duration,
+            );
+            target.upsert_effect(EffectData {
+                unique_name: ICEBORN_GAUNTLET_FIELD.to_string(),
+                expiry: TIME.with(|time| *time.borrow() + field.duration),
+                result: EffectResult::DamageReductionAgainst {
+                    target: attacker,
+                    percent: field.damage_reduction,
+                },
+            });
+        }
+    };
+}
+
+static SPELLBLADE_DAMAGE: Lazy<HashMap<AbilityName, WikiDamage>> = Lazy::new(|| {
+    SPELLBLADES
+        .into_iter()
+        .map(|(item_name, name)| (name, WikiDamage::load(item_name, "Spellblade")))
+        .collect()
+});
+
+/**
+ * Abilities any champion can cast, built once and shared by every thread
+ */
+pub static STATIC_ABILITIES: Lazy<HashMap<AbilityName, Box<AbilityFn>>> = Lazy::new(|| {
+    let mut m: HashMap<AbilityName, Box<AbilityFn>> = HashMap::new();
+    let auto_attack = get_auto_attack_ability();
+    m.insert(
+        AbilityName::NIGHTSTALKER,
+        Box::new(get_wiki_damage_ability(
+            "Duskblade of Draktharr",
+            "Nightstalker",
+        )),
+    );
+    m.insert(
+        AbilityName::MistsEdge,
+        Box::new(get_wiki_damage_ability(
+            "Blade of the Ruined King",
+            "Mist's Edge",
+        )),
+    );
+    for (item_name, name) in SPELLBLADES {
+        m.insert(
+            name,
+            Box::new(get_wiki_damage_ability(item_name, "Spellblade")),
+        );
+    }
+    m.insert(AbilityName::AUTO, Box::new(auto_attack));
+    m.extend(rune_abilities());
+    m.extend(summoner_abilities());
+    m.extend(item_active_abilities());
+    return m;
+});
+
+/**
+ * Damage the spellblade proc would deal to the target, used to pick the strongest one
+ */
+pub fn spellblade_damage(name: &AbilityName, attacker: &Champion, target: &Champion) -> f64 {
+    SPELLBLADE_DAMAGE
+        .get(name)
+        .map_or(0.0, |damage| damage.mitigated(attacker, target))
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct UnknownItemEffect {
     #[serde(default)]
     pub name: String,
+    #[serde(default)]
     pub description: String,
+    #[serde(default)]
     pub unique: bool,
+    #[serde(default)]
+    pub cd: Option<String>,
+}
+
+impl UnknownItemEffect {
+    pub fn cooldown(&self) -> Option<WikiQuantity> {
+        self.cd.as_deref().and_then(parse_cooldown)
+    }
+
+    /**
+     * Damage clause of the description, ie nightstalker's "deals 75 (+ 30% bonus AD) bonus physical damage"
+     */
+    pub fn damage_terms(&self) -> Vec<WikiTerm> {
+        parse_damage_terms(&self.description)
+    }
+}
+
+/**
+ * Values wiki terms scale with, read from the sheets of the hit
+ */
+pub fn scaling_values(attacker: &Champion, target: &Champion, sheets: &HitSheets) -> ScalingValues {
+    let sheet = &sheets.attacker;
+    return ScalingValues {
+        is_ranged: attacker.is_ranged(),
+        level: attacker.level,
+        base_ad: sheet.attack_damage.base,
+        bonus_ad: sheet.attack_damage.bonus,
+        ability_power: sheet.ability_power.total(),
+        max_health: sheet.health.total(),
+        bonus_health: sheet.health.bonus,
+        missing_health: sheet.health.total() - attacker.current_health,
+        armor: sheet.armor.total(),
+        bonus_armor: sheet.armor.bonus,
+        magic_resist: sheet.magic_resist.total(),
+        bonus_magic_resist: sheet.magic_resist.bonus,
+        target_max_health: sheets.target.health.total(),
+        target_current_health: target.current_health,
+    };
 }
 
 pub trait ChampionApplyable {
@@ -107,15 +364,15 @@ pub struct OnHit {
     pub mode: OnHitActivation,
 }
 
-impl From<(&OnHit, Weak<RefCell<Champion>>)> for EffectData {
-    fn from(tuple: (&OnHit, Weak<RefCell<Champion>>)) -> Self {
-        let (on_hit, attacker_ref) = tuple;
+impl From<(&OnHit, EntityId)> for EffectData {
+    fn from(tuple: (&OnHit, EntityId)) -> Self {
+        let (on_hit, attacker) = tuple;
         EffectData {
             unique_name: on_hit.name.to_string(),
             expiry: TIME.with(|time| *time.borrow() + on_hit.ttl.unwrap_or(f64::INFINITY)),
             result: EffectResult::EmpowerNextAttack(EmpowerState::Active(
                 AbilityEffect {
-                    attacker: attacker_ref,
+                    attacker,
                     name: on_hit.name.clone(),
                     data: CastingData {
                         ..Default::default()
@@ -130,6 +387,7 @@ impl From<(&OnHit, Weak<RefCell<Champion>>)> for EffectData {
 pub enum OnHitActivation {
     Auto,
     ActiveSpell,
+    EveryAuto, // cast on every basic attack, no empower or cooldown
 }
 
 #[derive(Debug)]
@@ -141,6 +399,7 @@ pub struct StatItemEffect {
 pub enum ConcreteItemEffect {
     StatItemEffect(StatItemEffect),
     OnHit(OnHit),
+    PermanentEffect(EffectData),
     UnhandledItemEffect(UnhandledItemEffect),
 }
 
@@ -150,6 +409,9 @@ impl ChampionApplyable for ConcreteItemEffect {
             ConcreteItemEffect::StatItemEffect(v) => v.apply_to_champ(champion),
             ConcreteItemEffect::UnhandledItemEffect(v) => v.apply_to_champ(champion),
             ConcreteItemEffect::OnHit(v) => v.apply_to_champ(champion),
+            ConcreteItemEffect::PermanentEffect(v) => {
+                champion.upsert_effect(v);
+            }
         }
     }
 }
@@ -176,30 +438,71 @@ impl ChampionApplyable for UnhandledItemEffect {
     }
 }
 
+/**
+ * Terms of the description after the phrase, none if the phrase is missing
+ */
+fn terms_after(description: &str, phrase: &str) -> Vec<WikiTerm> {
+    return match description.find(phrase) {
+        Some(start) => parse_terms(&description[start + phrase.len()..]),
+        None => Vec::new(),
+    };
+}
+
+/**
+ * First number of the description after the phrase, ie 10 in iceborn gauntlet's "damage against you reduced by 10%"
+ */
+fn value_after(description: &str, phrase: &str) -> Option<WikiQuantity> {
+    return terms_after(description, phrase)
+        .into_iter()
+        .next()
+        .map(|term| term.quantity);
+}
+
+/**
+ * Percent and duration of the slow after the phrase, ie nightstalker's "slows the target by 99% for 0.25 seconds"
+ */
+fn parse_slow(description: &str, phrase: &str) -> Option<(f64, f64)> {
+    let mut terms = terms_after(description, phrase).into_iter();
+    let slow = terms.next().filter(|term| term.is_percent)?;
+    let duration = terms.next()?;
+    return Some((
+        slow.quantity.value(false, 1),
+        duration.quantity.value(false, 1),
+    ));
+}
+
+/**
+ * Percent of maximum health below which the effect executes, ie the collector's "below 5% of their maximum health"
+ */
+fn execute_threshold(effect: &UnknownItemEffect) -> Option<f64> {
+    return parse_terms(&effect.description)
+        .into_iter()
+        .find(|term| term.is_percent && term.stat == Some(ScalingStat::MaxHealth))
+        .map(|term| term.quantity.value(false, 1));
+}
+
+/**
+ * Cooldown as a melee champion at level 1, the effects we model have flat cooldowns
+ */
+fn flat_cooldown(effect: &UnknownItemEffect) -> f64 {
+    return effect
+        .cooldown()
+        .unwrap_or_else(|| panic!("{} should have a cooldown", effect.name))
+        .value(false, 1);
+}
+
 impl From<(&UnknownItemEffect, &str)> for ConcreteItemEffect {
     fn from(tuple: (&UnknownItemEffect, &str)) -> ConcreteItemEffect {
         let (incoming, item_name) = tuple;
         return match incoming.name.as_str() {
-            "Spellblade" => match item_name {
-                "Sheen" => ConcreteItemEffect::OnHit(OnHit {
-                    ttl: Some(10.0),
-                    name: AbilityName::SpellbladeSheen,
-                    mode: OnHitActivation::ActiveSpell,
-                    cooldown: 1.5,
-                }),
-                "Essence Reaver" => ConcreteItemEffect::OnHit(OnHit {
+            "Spellblade" => match SPELLBLADES.iter().find(|(name, _)| *name == item_name) {
+                Some((_, name)) => ConcreteItemEffect::OnHit(OnHit {
                     ttl: Some(10.0),
-                    name: AbilityName::SpellbladeEssenceReaver,
+                    name: name.clone(),
                     mode: OnHitActivation::ActiveSpell,
-                    cooldown: 1.5,
+                    cooldown: flat_cooldown(incoming),
                 }),
-                "Divine Sunderer" => ConcreteItemEffect::OnHit(OnHit {
-                    ttl: Some(10.0),
-                    name: AbilityName::SpellbladeDivineSunderer,
-                    mode: OnHitActivation::ActiveSpell,
-                    cooldown: 1.5,
-                }),
-                _ => ConcreteItemEffect::UnhandledItemEffect(UnhandledItemEffect {
+                None => ConcreteItemEffect::UnhandledItemEffect(UnhandledItemEffect {
                     name: incoming.name.clone(),
                     description: incoming.description.clone(),
                 }),
@@ -208,8 +511,25 @@ impl From<(&UnknownItemEffect, &str)> for ConcreteItemEffect {
                 ttl: None,
                 name: AbilityName::NIGHTSTALKER,
                 mode: OnHitActivation::Auto,
-                cooldown: 15.0,
+                cooldown: flat_cooldown(incoming),
+            }),
+            "Mist's Edge" => ConcreteItemEffect::OnHit(OnHit {
+                ttl: None,
+                name: AbilityName::MistsEdge,
+                mode: OnHitActivation::EveryAuto,
+                cooldown: 0.0,
             }),
+            "Death and Taxes" => match execute_threshold(incoming) {
+                Some(threshold) => ConcreteItemEffect::PermanentEffect(EffectData {
+                    unique_name: incoming.name.clone(),
+                    expiry: f64::INFINITY,
+                    result: EffectResult::ExecuteThreshold(threshold),
+                }),
+                None => ConcreteItemEffect::UnhandledItemEffect(UnhandledItemEffect {
+                    name: incoming.name.clone(),
+                    description: incoming.description.clone(),
+                }),
+            },
             "Gouge" => ConcreteItemEffect::StatItemEffect(StatItemEffect {
                 stats: Box::new(WikiItemStatDeltas {
                     lethality: Some(10.0),
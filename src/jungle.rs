@@ -0,0 +1,324 @@
+use std::{collections::HashMap, fs::File, io::prelude::*};
+
+use memoize::memoize;
+use serde::Deserialize;
+
+use crate::{
+    champions::champion::{AbilityName, CastingData, Champion},
+    geometry::Position,
+    summoner_spells::SummonerSpell,
+    time_manager::{reset_time, TIME},
+    timeline::level_from_experience,
+    units::MonsterKind,
+    world::{EntityId, World},
+};
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct Camp {
+    pub monsters: Vec<MonsterKind>, // the large monster first
+    pub position: Position,
+    pub spawn: f64, // seconds into the game
+    pub gold: f64,
+    pub experience: f64,
+}
+
+#[memoize]
+pub fn open_jungle_camps_json() -> HashMap<String, Camp> {
+    let mut file = File::open("data/jungle_camps.json").expect("Could not open file");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .expect("Could not read file");
+
+    return serde_json::from_str(&contents).expect("could not unmarshal");
+}
+
+#[memoize]
+pub fn load_jungle_camp(name: String) -> Camp {
+    return open_jungle_camps_json()
+        .get(&name)
+        .cloned()
+        .unwrap_or_else(|| panic!("unknown jungle camp {}", name));
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CampClear {
+    pub camp: String,
+    pub start: f64,    // seconds into the clear when the fight started
+    pub duration: f64, // fight only, walking to the camp excluded
+    pub health: f64,   // left once the camp is down
+    pub level: u8,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClearResult {
+    pub camps: Vec<CampClear>,
+    pub total_time: f64,
+    pub remaining_health: f64,
+    pub gold: f64,
+    pub died: bool,
+}
+
+pub type Rotation = fn() -> Vec<(AbilityName, CastingData)>;
+
+/**
+ * Clears camps in order, walking between them in straight lines. Each camp opens with the rotation
+ * (at the ranks learned by that point) on the large monster, then autos until every monster is dead
+ * while the monsters hit back
+ */
+pub struct JungleClear {
+    pub route: Vec<String>,
+    pub rotation: Rotation,
+    pub smite: Option<SummonerSpell>,
+    pub monster_level: u8,
+}
+
+impl JungleClear {
+    const MAX_CAMP_TIME: f64 = 120.0;
+
+    pub fn new(route: &[&str], rotation: Rotation) -> JungleClear {
+        return JungleClear {
+            route: route.iter().map(|camp| camp.to_string()).collect(),
+            rotation,
+            smite: Some(SummonerSpell::Smite),
+            monster_level: 1,
+        };
+    }
+
+    /**
+     * Starts at the first camp at the start of the game, leveling up with the experience of each camp
+     * cleared. Camps that have not spawned yet are waited for
+     */
+    pub fn run(&self, champion: Champion) -> ClearResult {
+        let now = || TIME.with(|time| *time.borrow());
+        reset_time();
+        let start = now();
+        let start_level = champion.level;
+        let mut world = World::new();
+        let champion = world.spawn(champion);
+        if let Some(first) = self.route.first() {
+            world[champion].position = load_jungle_camp(first.clone()).position;
+        }
+        let mut result = ClearResult {
+            camps: Vec::new(),
+            total_time: 0.0,
+            remaining_health: world[champion].current_health,
+            gold: 0.0,
+            died: false,
+        };
+        let mut experience = 0.0;
+        for name in &self.route {
+            let camp = load_jungle_camp(name.clone());
+            let walk = world[champion].position.distance(&camp.position)
+                / world[champion].get_move_speed();
+            world[champion].move_towards(&camp.position, walk);
+            advance_all(&mut world, champion, walk);
+            advance_all(&mut world, champion, camp.spawn - now());
+
+            let fight_start = now();
+            let monsters: Vec<EntityId> = camp
+                .monsters
+                .iter()
+                .map(|kind| {
+                    let mut monster = Champion::new_monster(*kind, self.monster_level);
+                    monster.position = camp.position;
+                    world.spawn(monster)
+                })
+                .collect();
+            self.fight(&mut world, champion, &monsters);
+
+            if world[champion].current_health <= 0.0 {
+                result.died = true;
+                break;
+            }
+            experience += camp.experience;
+            result.gold += camp.gold;
+            let level = start_level.max(level_from_experience(experience));
+            world[champion].set_level(level);
+            result.camps.push(CampClear {
+                camp: name.clone(),
+                start: fight_start - start,
+                duration: now() - fight_start,
+                health: world[champion].current_health,
+                level,
+            });
+        }
+        result.total_time = now() - start;
+        result.remaining_health = world[champion].current_health;
+        return result;
+    }
+
+    fn fight(&self, world: &mut World, champion: EntityId, monsters: &[EntityId]) {
+        let now = || TIME.with(|time| *time.borrow());
+        let alive = |world: &World| {
+            monsters
+                .iter()
+                .position(|monster| world[*monster].current_health > 0.0)
+        };
+        let Some(primary) = alive(world) else {
+            return;
+        };
+        let rotation = (self.rotation)();
+        world.execute_combo_on_targets(champion, rotation, monsters, primary);
+
+        let fight_start = now();
+        let mut champion_next = fight_start;
+        let mut monster_next = vec![fight_start; monsters.len()];
+        while let Some(primary) = alive(world) {
+            if world[champion].current_health <= 0.0
+                || now() - fight_start > JungleClear::MAX_CAMP_TIME
+            {
+                return;
+            }
+            self.try_smite(world, champion, monsters[primary]);
+            let next_monster = monsters
+                .iter()
+                .enumerate()
+                .filter(|(_, monster)| {
+                    world[**monster].current_health > 0.0
+                        && world[**monster].get_attack_speed().get_attacks_per_second() > 0.0
+                })
+                .map(|(index, _)| (monster_next[index], index))
+                .min_by(|(left, _), (right, _)| left.total_cmp(right));
+
+            match next_monster {
+                Some((time, index)) if time < champion_next => {
+                    advance_all(world, champion, time - now());
+                    let monster = monsters[index];
+                    let damage = world[monster].get_base_ad();
+                    world.deal_damage(monster, champion, damage);
+                    monster_next[index] +=
+                        1.0 / world[monster].get_attack_speed().get_attacks_per_second();
+                }
+                _ => {
+                    advance_all(world, champion, champion_next - now());
+                    let auto = vec![(AbilityName::AUTO, CastingData::default())];
+                    world.execute_combo_on_targets(champion, auto, monsters, primary);
+                    champion_next =
+                        now() + 1.0 / world[champion].get_attack_speed().get_attacks_per_second();
+                }
+            }
+        }
+    }
+
+    /**
+     * Smites the monster once smite alone would finish it
+     */
+    fn try_smite(&self, world: &mut World, champion: EntityId, monster: EntityId) {
+        let Some(smite) = self.smite else {
+            return;
+        };
+        if world[monster].current_health <= smite.smite_damage().unwrap_or(0.0) {
+            world.execute_ability(
+                champion,
+                &AbilityName::Summoner(smite),
+                monster,
+                &CastingData::default(),
+            );
+        }
+    }
+}
+
+/**
+ * Moves the clock forward for everyone in the fight, the champion regenerating health (per 5 seconds) meanwhile
+ */
+fn advance_all(world: &mut World, champion: EntityId, seconds: f64) {
+    let seconds = seconds.max(0.0);
+    let regen = world[champion].stat_sheet().health_regen.total() * seconds / 5.0;
+    world[champion].heal(regen);
+    world.advance_time(seconds);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        champions::{
+            skill_order::{Skill, SkillOrder},
+            Vi,
+        },
+        load_wiki_item::apply_items_to_champ,
+    };
+    use rstest::rstest;
+
+    const FULL_CLEAR: [&str; 6] = ["Red", "Krugs", "Raptors", "Wolves", "Blue", "Gromp"];
+
+    fn vi_with(level: u8, item_names: &[&str]) -> Champion {
+        let mut vi = Champion::new_with_skill_order(
+            Vi::NAME.to_string(),
+            level,
+            SkillOrder::max_by_priority([Skill::Q, Skill::E, Skill::W]),
+            Vi::new().get_name_closures(),
+        );
+        apply_items_to_champ(item_names, &mut vi);
+        return vi;
+    }
+
+    #[rstest]
+    fn test_every_camp_loads() {
+        FULL_CLEAR.iter().for_each(|name| {
+            assert!(!load_jungle_camp(name.to_string()).monsters.is_empty());
+        });
+    }
+
+    #[rstest]
+    fn test_full_clear() {
+        let clear = JungleClear::new(&FULL_CLEAR, Vi::jungle_rotation);
+        let result = clear.run(vi_with(9, &["Scorchclaw Pup"]));
+        assert!(!result.died);
+        assert_eq!(FULL_CLEAR.len(), result.camps.len());
+        assert!(result.remaining_health > 0.0);
+        assert!(result.camps.last().unwrap().level >= 9);
+        let fights: f64 = result.camps.iter().map(|camp| camp.duration).sum();
+        assert!(fights > 0.0 && fights < result.total_time);
+        // camps are cleared one after the other
+        result.camps.windows(2).for_each(|pair| {
+            assert!(pair[1].start >= pair[0].start + pair[0].duration);
+        });
+    }
+
+    #[rstest]
+    fn test_level_one_vi_dies_on_the_full_clear() {
+        let clear = JungleClear::new(&FULL_CLEAR, Vi::jungle_rotation);
+        let result = clear.run(vi_with(1, &["Scorchclaw Pup"]));
+        assert!(result.died);
+        assert!(result.camps.len() < FULL_CLEAR.len());
+        assert!(result.remaining_health <= 0.0);
+    }
+
+    #[rstest]
+    fn test_items_speed_up_the_clear() {
+        let clear = JungleClear::new(&FULL_CLEAR[..2], Vi::jungle_rotation);
+        let slow = clear.run(vi_with(9, &[]));
+        let fast = clear.run(vi_with(9, &["B. F. Sword", "Pickaxe"]));
+        assert!(fast.total_time < slow.total_time);
+    }
+
+    #[rstest]
+    fn test_companion_speeds_up_the_clear() {
+        let companion = vi_with(9, &["Scorchclaw Pup"]);
+        assert_eq!(20.0, companion.stat_sheet().monster_damage);
+        let clear = JungleClear::new(&["Red"], Vi::jungle_rotation);
+        let without = clear.run(vi_with(9, &[]));
+        let with = clear.run(companion);
+        assert!(with.camps[0].duration < without.camps[0].duration);
+    }
+
+    #[rstest]
+    fn test_smite_speeds_up_the_clear() {
+        let mut clear = JungleClear::new(&["Red"], Vi::jungle_rotation);
+        let smite = clear.run(vi_with(9, &[]));
+        clear.smite = None;
+        let no_smite = clear.run(vi_with(9, &[]));
+        assert!(smite.camps[0].duration < no_smite.camps[0].duration);
+    }
+
+    #[rstest]
+    fn test_fights_wait_for_the_camp_to_spawn() {
+        let clear = JungleClear::new(&["Gromp"], Vi::jungle_rotation);
+        let result = clear.run(vi_with(9, &[]));
+        assert_eq!(
+            load_jungle_camp("Gromp".to_string()).spawn,
+            result.camps[0].start
+        );
+    }
+}
@@ -1,10 +1,25 @@
+#![allow(clippy::needless_return)]
 pub mod armor_reducer;
 pub mod attack;
 pub mod champions;
 pub mod core;
+pub mod geometry;
+pub mod gold_efficiency;
+pub mod item_actives;
 pub mod item_effects;
+pub mod jungle;
 pub mod load_champion;
 pub mod load_dd_item;
+pub mod load_monster;
 pub mod load_wiki_item;
+pub mod runes;
+pub mod scenario;
+pub mod stat_sheet;
+pub mod summoner_spells;
+pub mod sweep;
 pub mod target;
 pub mod time_manager;
+pub mod timeline;
+pub mod units;
+pub mod wiki_template;
+pub mod world;
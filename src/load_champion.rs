@@ -1,10 +1,12 @@
 use memoize::memoize;
 
+use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json;
 use serde_json::Value;
 
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::fs::File;
 use std::io::prelude::*;
@@ -59,6 +61,8 @@ pub struct ChampionStats {
     #[serde(skip)]
     pub life_steal: f64,
     #[serde(skip)]
+    pub percent_health: f64, // increases maximum health, ie heartsteel's mythic passive
+    #[serde(skip)]
     pub percent_movement_speed: f64,
     #[serde(skip)]
     pub ability_haste: f64,
@@ -70,51 +74,133 @@ pub struct ChampionStats {
     pub percent_armor_pen: f64,
     #[serde(skip)]
     pub lethality: f64,
+    #[serde(skip)]
+    pub flat_magic_pen: f64,
+    #[serde(skip)]
+    pub percent_magic_pen: f64,
+    #[serde(skip)]
+    pub percent_base_health_regen: f64,
+    #[serde(skip)]
+    pub percent_base_mana_regen: f64,
+    #[serde(skip)]
+    pub tenacity: f64,
+    #[serde(skip)]
+    pub monster_damage: f64, // percent bonus against non-epic monsters
+    #[serde(skip)]
+    pub attack_speed_ratio: Option<f64>, // scaling of bonus attack speed, the base attack speed when unset
+    #[serde(skip)]
+    pub attack_damage_multiplier: Option<f64>, // share of attack damage dealt by basic attacks, all of it when unset
+}
+
+/**
+ * Attack values ddragon leaves out, only listed for champions that differ from the defaults
+ */
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct ChampionAttackOverrides {
+    pub attack_speed_ratio: Option<f64>,
+    pub attack_damage_multiplier: Option<f64>,
+}
+
+#[memoize]
+pub fn open_champion_attack_json() -> HashMap<String, ChampionAttackOverrides> {
+    let mut file = File::open("data/champion_attack.json").expect("Could not open file");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .expect("Could not read file");
+
+    return serde_json::from_str(&contents).expect("could not unmarshal");
 }
 
 pub trait ChampionStatModifier: Debug {
     fn modify_champion_stats(&self, stats: &mut ChampionStats);
 }
 
-#[memoize]
-pub fn open_champion_json() -> Option<Value> {
+fn open_champion_json() -> Value {
     let mut file = File::open("data/champion.json").expect("Could not open file");
     let mut contents = String::new();
     file.read_to_string(&mut contents)
         .expect("Could not read file");
 
-    let full_value: Value = serde_json::from_str(&contents).expect("could not unmarshal");
-    return full_value.get("data").map(|v| v.to_owned());
+    let mut full_value: Value = serde_json::from_str(&contents).expect("could not unmarshal");
+    return full_value["data"].take();
 }
 
-pub fn load_champion_names() -> Vec<String> {
-    let data = open_champion_json().unwrap();
-    let mut names = Vec::new();
-    if let Value::Object(map) = data {
-        for (name, _) in map {
-            names.push(name);
+/**
+ * Stats of every champion (attack overrides included), parsed once and indexed by name and by riot key
+ */
+pub struct ChampionCatalog {
+    stats: HashMap<String, ChampionStats>,
+    names_by_key: HashMap<String, String>,
+    names: Vec<String>, // sorted
+}
+
+impl ChampionCatalog {
+    fn from_champion_json() -> ChampionCatalog {
+        let Value::Object(data) = open_champion_json() else {
+            panic!("champion data is not an object");
+        };
+        let overrides = open_champion_attack_json();
+        let mut catalog = ChampionCatalog {
+            stats: HashMap::new(),
+            names_by_key: HashMap::new(),
+            names: Vec::new(),
+        };
+        for (name, mut value) in data {
+            let mut stats: ChampionStats = serde_json::from_value(value["stats"].take()).unwrap();
+            if let Some(overrides) = overrides.get(&name) {
+                stats.attack_speed_ratio = overrides.attack_speed_ratio;
+                stats.attack_damage_multiplier = overrides.attack_damage_multiplier;
+            }
+            if let Some(key) = value["key"].as_str() {
+                catalog.names_by_key.insert(key.to_string(), name.clone());
+            }
+            catalog.stats.insert(name.clone(), stats);
+            catalog.names.push(name);
         }
+        catalog.names.sort();
+        return catalog;
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ChampionStats> {
+        return self.stats.get(name);
+    }
+
+    /**
+     * Lookup by the numeric riot key, ie "254" for vi
+     */
+    pub fn get_by_key(&self, key: &str) -> Option<&ChampionStats> {
+        return self
+            .names_by_key
+            .get(key)
+            .and_then(|name| self.stats.get(name));
+    }
+
+    pub fn names(&self) -> &[String] {
+        return &self.names;
     }
-    return names;
 }
 
-#[memoize]
+static CHAMPIONS: Lazy<ChampionCatalog> = Lazy::new(ChampionCatalog::from_champion_json);
+
+pub fn load_champion_catalog() -> &'static ChampionCatalog {
+    &CHAMPIONS
+}
+
+pub fn load_champion_names() -> Vec<String> {
+    return load_champion_catalog().names().to_vec();
+}
+
 pub fn load_champion_stats(champion_name: String) -> ChampionStats {
-    let data = open_champion_json().unwrap();
-    let champion_stats_json = data
-        .get(champion_name)
-        .and_then(|value| value.get("stats"))
-        .unwrap();
-
-    // why is this clone needed?
-    let champion_stats: ChampionStats =
-        serde_json::from_value(champion_stats_json.clone()).unwrap();
-    return champion_stats;
+    return load_champion_catalog()
+        .get(&champion_name)
+        .cloned()
+        .unwrap_or_else(|| panic!("unknown champion {}", champion_name));
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{attack::BasicAttack, champions::Vi};
+    use crate::{attack::BasicAttack, champions::Vi, stat_sheet::StatSheet};
 
     use super::*;
     use rstest::rstest;
@@ -125,10 +211,37 @@ mod tests {
         assert_eq!(stats.critical_strike_chance, 0.0);
     }
 
+    #[rstest]
+    fn test_catalog_lookups() {
+        let catalog = load_champion_catalog();
+        let vi = catalog.get(Vi::NAME).unwrap();
+        assert!(std::ptr::eq(vi, catalog.get_by_key("254").unwrap()));
+        assert!(std::ptr::eq(
+            vi,
+            load_champion_catalog().get(Vi::NAME).unwrap()
+        ));
+        assert!(catalog.get("Teemo's Mushroom").is_none());
+        assert!(catalog.names().windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
     #[rstest]
     fn test_load_champion_basic_attack() {
         let stats = load_champion_stats(Vi::NAME.to_string());
         let attack: BasicAttack = (&stats, 5).into();
         assert_eq!(72.0, attack.base_attack_damage.round()); // values from game, patch 13.6
     }
+
+    #[rstest]
+    #[case("Kalista", 0.694, 0.9)]
+    #[case("Caitlyn", 0.568, 1.0)]
+    #[case("Vi", 0.644, 1.0)]
+    fn test_attack_overrides(
+        #[case] name: &str,
+        #[case] expected_ratio: f64,
+        #[case] expected_multiplier: f64,
+    ) {
+        let sheet = StatSheet::from((&load_champion_stats(name.to_string()), 1));
+        assert_eq!(expected_ratio, sheet.attack_speed_ratio);
+        assert_eq!(expected_multiplier, sheet.attack_damage_multiplier);
+    }
 }
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fs::File, io::Read};
 
-use memoize::memoize;
+use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
@@ -39,38 +39,71 @@ pub struct DDItemStatDeltas {
     pub percent_movement_speed: Option<f64>,
 }
 
-#[memoize]
-pub fn open_dd_item_json() -> Value {
+/**
+ * Every ddragon item by id, only read while building the catalog
+ */
+fn read_dd_item_json() -> serde_json::Map<String, Value> {
     let mut file = File::open("data/item.json").expect("Could not open file");
     let mut contents = String::new();
     file.read_to_string(&mut contents)
         .expect("Could not read file");
 
-    let full_value: Value = serde_json::from_str(&contents).expect("could not unmarshal");
-    return full_value.get("data").map(|v| v.to_owned()).unwrap();
+    let mut full_value: Value = serde_json::from_str(&contents).expect("could not unmarshal");
+    return match full_value["data"].take() {
+        Value::Object(items) => items,
+        _ => panic!("ddragon items should be an object"),
+    };
 }
 
-fn name_to_id_map() -> HashMap<String, String> {
-    let mut output_map = HashMap::new();
-    let all_items = load_items();
-    all_items.iter().for_each(|(key, value)| {
-        let name = value.get("name").and_then(|v| v.as_str()).unwrap();
-        output_map.entry(name.to_string()).or_insert(key.to_owned());
-        return;
-    });
-    return output_map;
+/**
+ * Stats of the purchasable summoners rift items, parsed once and indexed by id and by name
+ */
+pub struct DDItemCatalog {
+    stats: HashMap<String, DDItemStatDeltas>,
+    ids_by_name: HashMap<String, String>, // names shared by several ids resolve to the lowest id
+}
+
+impl DDItemCatalog {
+    fn from_dd_json() -> DDItemCatalog {
+        let mut catalog = DDItemCatalog {
+            stats: HashMap::new(),
+            ids_by_name: HashMap::new(),
+        };
+        for (id, value) in load_items() {
+            let name = value.get("name").and_then(|v| v.as_str()).unwrap();
+            catalog
+                .ids_by_name
+                .entry(name.to_string())
+                .or_insert(id.clone());
+            let stats = serde_json::from_value(value["stats"].clone()).unwrap();
+            catalog.stats.insert(id, stats);
+        }
+        return catalog;
+    }
+
+    pub fn get(&self, id: &str) -> Option<&DDItemStatDeltas> {
+        return self.stats.get(id);
+    }
+
+    pub fn id_of(&self, name: &str) -> Option<&str> {
+        return self.ids_by_name.get(name).map(|id| id.as_str());
+    }
+
+    pub fn get_by_name(&self, name: &str) -> Option<&DDItemStatDeltas> {
+        return self.id_of(name).and_then(|id| self.get(id));
+    }
+}
+
+static DD_ITEMS: Lazy<DDItemCatalog> = Lazy::new(DDItemCatalog::from_dd_json);
+
+pub fn load_dd_item_catalog() -> &'static DDItemCatalog {
+    &DD_ITEMS
 }
 
-pub fn load_dd_item(name: &str) -> DDItemStatDeltas {
-    let map = name_to_id_map();
-    let id = map.get(name).unwrap();
-    let item_value = load_items()
-        .get(id)
-        .and_then(|v| v.get("stats"))
-        .unwrap()
-        .clone();
-    let item_obj: DDItemStatDeltas = serde_json::from_value(item_value).unwrap();
-    return item_obj;
+pub fn load_dd_item(name: &str) -> &'static DDItemStatDeltas {
+    return load_dd_item_catalog()
+        .get_by_name(name)
+        .unwrap_or_else(|| panic!("unknown item {}", name));
 }
 
 impl ChampionStatModifier for DDItemStatDeltas {
@@ -89,8 +122,7 @@ impl ChampionStatModifier for DDItemStatDeltas {
 }
 
 fn load_items() -> serde_json::Map<std::string::String, Value> {
-    let json_value = open_dd_item_json();
-    let mut filtered_items = json_value.as_object().unwrap().clone();
+    let mut filtered_items = read_dd_item_json();
     filtered_items.retain(|_key, value| {
         let purchasable = value
             .get("gold")
@@ -125,9 +157,14 @@ mod tests {
     }
 
     #[rstest]
-    fn test_name_to_id_map() {
-        let id_map = name_to_id_map();
-        assert_eq!(id_map.get("Long Sword").unwrap(), "1036");
+    fn test_catalog_lookups() {
+        let catalog = load_dd_item_catalog();
+        assert_eq!(Some("1036"), catalog.id_of("Long Sword"));
+        assert!(std::ptr::eq(
+            catalog.get("1036").unwrap(),
+            load_dd_item("Long Sword")
+        ));
+        assert!(catalog.get("1035").is_none());
     }
 
     #[rstest]
@@ -0,0 +1,77 @@
+use memoize::memoize;
+use serde::Deserialize;
+use std::{collections::HashMap, fs::File, io::prelude::*};
+
+use crate::load_champion::ChampionStats;
+
+/**
+ * Jungle and epic monster stats, growing with the average champion level
+ */
+#[derive(Deserialize, Clone, Default, Debug, PartialEq)]
+#[serde(default)]
+pub struct MonsterStats {
+    pub health: f64,
+    pub health_per_level: f64,
+    pub armor: f64,
+    pub armor_per_level: f64,
+    pub magic_resist: f64,
+    pub magic_resist_per_level: f64,
+    pub attack_damage: f64,
+    pub attack_damage_per_level: f64,
+    pub attack_speed: f64,
+    pub attack_range: f64,
+    pub move_speed: f64,
+    pub damage_reduction: f64, // percent less damage from champions, ie baron's gaze on the champion it focuses
+}
+
+impl From<&MonsterStats> for ChampionStats {
+    fn from(monster: &MonsterStats) -> ChampionStats {
+        return ChampionStats {
+            health: monster.health,
+            health_per_level: monster.health_per_level,
+            armor: monster.armor,
+            armor_per_level: monster.armor_per_level,
+            magic_resist: monster.magic_resist,
+            magic_resist_per_level: monster.magic_resist_per_level,
+            base_attack_damage: monster.attack_damage,
+            attack_damage_per_level: monster.attack_damage_per_level,
+            attack_speed: monster.attack_speed,
+            attack_range: monster.attack_range,
+            move_speed: monster.move_speed,
+            ..Default::default()
+        };
+    }
+}
+
+#[memoize]
+pub fn open_monster_json() -> HashMap<String, MonsterStats> {
+    let mut file = File::open("data/monsters.json").expect("Could not open file");
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)
+        .expect("Could not read file");
+
+    return serde_json::from_str(&contents).expect("could not unmarshal");
+}
+
+#[memoize]
+pub fn load_monster_stats(name: String) -> MonsterStats {
+    return open_monster_json()
+        .get(&name)
+        .cloned()
+        .unwrap_or_else(|| panic!("unknown monster {}", name));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_load_monster_stats() {
+        let gromp = load_monster_stats("Gromp".to_string());
+        assert_eq!(2050.0, gromp.health);
+        assert_eq!(0.0, gromp.damage_reduction);
+        let stats: ChampionStats = (&gromp).into();
+        assert_eq!(70.0, stats.base_attack_damage);
+    }
+}
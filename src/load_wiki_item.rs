@@ -1,5 +1,4 @@
-use memoize::memoize;
-
+use once_cell::sync::Lazy;
 use serde::Deserialize;
 use serde_json::Value;
 use std::{collections::HashMap, fs::File, io::Read};
@@ -29,6 +28,8 @@ pub struct WikiItemStatDeltas {
     pub crit_chance: Option<f64>,
     #[serde(rename = "hp")]
     pub health: Option<f64>,
+    #[serde(rename = "hppercent")]
+    pub percent_health: Option<f64>, // only heartsteel's mythic passive, listed as "1%"
 
     #[serde(rename = "lethality")]
     pub lethality: Option<f64>,
@@ -52,27 +53,75 @@ pub struct WikiItemStatDeltas {
 
     #[serde(rename = "spec")]
     pub spec: Option<f64>,
+    #[serde(rename = "tenacity")]
+    pub tenacity: Option<f64>,
+    #[serde(rename = "monsterdamage")]
+    pub monster_damage: Option<f64>,
 
     #[serde(rename = "mp5")]
-    pub mp5: Option<f64>,
+    pub mp5: Option<f64>, // percent of base mana regen, ie faerie charm's 50
     #[serde(rename = "hsp")]
     pub heal_sheild_power: Option<f64>,
     #[serde(rename = "gp10")]
     pub gold_per_10: Option<f64>,
     #[serde(rename = "hp5")]
-    pub hp5: Option<f64>,
+    pub hp5: Option<f64>, // percent of base health regen, ie rejuvenation bead's 100
     #[serde(rename = "hp5flat")]
-    pub hp5flat: Option<f64>,
+    pub hp5flat: Option<f64>, // health per 5 seconds
 }
 
-#[memoize]
-pub fn open_wiki_item_json() -> HashMap<String, Value> {
+static WIKI_ITEMS: Lazy<HashMap<String, Value>> = Lazy::new(|| {
     let mut file = File::open("data/wiki_items.json").expect("Could not open file");
     let mut contents = String::new();
     file.read_to_string(&mut contents)
         .expect("Could not read file");
 
     return serde_json::from_str(&contents).expect("could not unmarshal");
+});
+
+/**
+ * Every wiki item by name, read once and shared by all threads
+ */
+pub fn open_wiki_item_json() -> &'static HashMap<String, Value> {
+    &WIKI_ITEMS
+}
+
+impl WikiItemStatDeltas {
+    /**
+     * The deltas granted count times over, ie a mythic passive for each legendary. Summed before being
+     * applied, so percent penetration adds up instead of stacking multiplicatively
+     */
+    pub fn times(&self, count: f64) -> WikiItemStatDeltas {
+        let scale = |value: Option<f64>| value.map(|value| value * count);
+        return WikiItemStatDeltas {
+            attack_damage: scale(self.attack_damage),
+            ability_haste: scale(self.ability_haste),
+            ability_power: scale(self.ability_power),
+            armor: scale(self.armor),
+            percent_armor_pen: scale(self.percent_armor_pen),
+            attack_speed: scale(self.attack_speed),
+            crit_chance: scale(self.crit_chance),
+            health: scale(self.health),
+            percent_health: scale(self.percent_health),
+            lethality: scale(self.lethality),
+            lifesteal: scale(self.lifesteal),
+            omnivamp: scale(self.omnivamp),
+            percent_movement_speed: scale(self.percent_movement_speed),
+            flat_movement_speed: scale(self.flat_movement_speed),
+            magic_resist: scale(self.magic_resist),
+            mana: scale(self.mana),
+            percent_magic_pen: scale(self.percent_magic_pen),
+            flat_magic_pen: scale(self.flat_magic_pen),
+            spec: scale(self.spec),
+            tenacity: scale(self.tenacity),
+            monster_damage: scale(self.monster_damage),
+            mp5: scale(self.mp5),
+            heal_sheild_power: scale(self.heal_sheild_power),
+            gold_per_10: scale(self.gold_per_10),
+            hp5: scale(self.hp5),
+            hp5flat: scale(self.hp5flat),
+        };
+    }
 }
 
 impl ChampionStatModifier for WikiItemStatDeltas {
@@ -81,6 +130,7 @@ impl ChampionStatModifier for WikiItemStatDeltas {
         stats.magic_resist += self.magic_resist.unwrap_or(0.0);
         stats.health_regen += self.hp5flat.unwrap_or(0.0);
         stats.health += self.health.unwrap_or(0.0);
+        stats.percent_health += self.percent_health.unwrap_or(0.0);
         stats.mana += self.mana.unwrap_or(0.0);
         stats.bonus_attack_damage += self.attack_damage.unwrap_or(0.0);
         stats.bonus_attack_speed += self.attack_speed.unwrap_or(0.0);
@@ -96,65 +146,451 @@ impl ChampionStatModifier for WikiItemStatDeltas {
             stats.percent_armor_pen,
         );
         stats.omnivamp += self.omnivamp.unwrap_or(0.0);
+        stats.critical_strike_chance += self.crit_chance.unwrap_or(0.0);
+        stats.flat_magic_pen += self.flat_magic_pen.unwrap_or(0.0);
+        stats.percent_magic_pen = stack_multiplicative_reduction(
+            self.percent_magic_pen.unwrap_or(0.0),
+            stats.percent_magic_pen,
+        );
+        stats.percent_base_health_regen += self.hp5.unwrap_or(0.0);
+        stats.percent_base_mana_regen += self.mp5.unwrap_or(0.0);
+        stats.tenacity =
+            stack_multiplicative_reduction(self.tenacity.unwrap_or(0.0), stats.tenacity);
+        stats.monster_damage += self.monster_damage.unwrap_or(0.0);
     }
 }
 
-#[memoize]
-pub fn load_wiki_item_stats(name: String) -> WikiItemStatDeltas {
-    let all_items = open_wiki_item_json();
-    let maybe_stats = all_items.get(name.as_str()).unwrap().get("stats");
-    return match maybe_stats {
-        Some(stats) => serde_json::from_value(stats.clone()).unwrap(),
+/**
+ * Percent stats that only show up in spec text, ie mercury's treads "+30% [[tenacity]]" or the jungle
+ * companions "+20% damage against non-epic [[monster]]s"
+ */
+fn parse_spec_percent(spec: &str, stat: &str) -> Option<f64> {
+    if !spec.contains(stat) {
+        return None;
+    }
+    return spec
+        .trim_start_matches('+')
+        .split('%')
+        .next()
+        .and_then(|percent| percent.trim().parse().ok());
+}
+
+/**
+ * Some wiki stats are free text (ie spec, or ornn redirects), only numeric values are deltas
+ */
+fn parse_stat_deltas(stats: &Value) -> WikiItemStatDeltas {
+    let mut numeric_stats: serde_json::Map<String, Value> = stats
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(_, value)| value.is_number())
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .collect();
+    let percent_stats = stats
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(key, _)| *key != "spec")
+        .filter_map(|(key, value)| {
+            Some((key, value.as_str()?.strip_suffix('%')?.parse::<f64>().ok()?))
+        });
+    for (key, percent) in percent_stats {
+        match key.as_str() {
+            "hp" => numeric_stats.insert("hppercent".to_string(), Value::from(percent)),
+            _ => panic!("unhandled percent stat {}: {}%", key, percent),
+        };
+    }
+    let spec = stats
+        .get("spec")
+        .and_then(|spec| spec.as_str())
+        .unwrap_or("");
+    let spec_stats = [
+        ("tenacity", "[[tenacity]]"),
+        ("monsterdamage", "damage against non-epic [[monster]]s"),
+    ];
+    for (key, stat) in spec_stats {
+        if let Some(percent) = parse_spec_percent(spec, stat) {
+            numeric_stats.insert(key.to_string(), Value::from(percent));
+        }
+    }
+    return serde_json::from_value(Value::Object(numeric_stats)).unwrap();
+}
+
+/**
+ * List of names under the key, ie the item's "type" or "recipe", empty when missing
+ */
+fn parse_string_list(item: &Value, key: &str) -> Vec<String> {
+    return match item.get(key) {
+        Some(Value::Array(values)) => values
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    };
+}
+
+fn parse_item_stats(item: &Value) -> WikiItemStatDeltas {
+    return match item.get("stats") {
+        Some(stats) => parse_stat_deltas(stats),
         None => WikiItemStatDeltas {
             ..Default::default()
         },
     };
 }
 
-#[memoize]
-pub fn load_wiki_item_effects(name: String) -> Vec<UnknownItemEffect> {
-    let all_items = open_wiki_item_json();
-    let maybe_effects = all_items.get(name.as_str()).unwrap().get("effects");
-    if let None = maybe_effects {
+/**
+ * Passives of the item, ornn upgrades redirect theirs to the base item (ie "=&gt;Trinity Force")
+ */
+fn parse_item_effects(item: &Value) -> Vec<UnknownItemEffect> {
+    let Some(effects_value) = item.get("effects") else {
         return Vec::new();
-    }
-    let effects_value = maybe_effects.unwrap();
-
+    };
     let all_effects: HashMap<String, Value> =
         serde_json::from_value(effects_value.to_owned()).expect("could not deserialze effect map");
-    let passive_values: HashMap<String, Value> = all_effects
+    return all_effects
         .into_iter()
         .filter(|(key, _)| key.starts_with("pass"))
+        .map(|(key, value)| match value.as_str() {
+            Some(redirect) => {
+                let base_item = redirect.trim_start_matches("=&gt;");
+                open_wiki_item_json()[base_item]["effects"][&key].to_owned()
+            }
+            None => value,
+        })
+        .map(|value| serde_json::from_value(value).unwrap())
         .collect();
+}
 
-    return passive_values
-        .into_iter()
-        .map(|(_, value)| serde_json::from_value(value).unwrap())
-        .collect();
+/**
+ * Stats, passives, mythic stats, costs and recipes of every wiki item, parsed once and shared by all threads
+ */
+pub struct WikiItemCatalog {
+    stats: HashMap<String, WikiItemStatDeltas>,
+    effects: HashMap<String, Vec<UnknownItemEffect>>,
+    mythic_stats: HashMap<String, WikiItemStatDeltas>, // mythics only
+    types: HashMap<String, Vec<String>>,
+    costs: HashMap<String, f64>, // items with a buy price only
+    recipes: HashMap<String, Vec<String>>,
+    names: Vec<String>, // summoners rift items, sorted
+}
+
+impl WikiItemCatalog {
+    fn from_wiki_json() -> WikiItemCatalog {
+        let mut catalog = WikiItemCatalog {
+            stats: HashMap::new(),
+            effects: HashMap::new(),
+            mythic_stats: HashMap::new(),
+            types: HashMap::new(),
+            costs: HashMap::new(),
+            recipes: HashMap::new(),
+            names: Vec::new(),
+        };
+        for (name, item) in open_wiki_item_json() {
+            catalog.stats.insert(name.clone(), parse_item_stats(item));
+            catalog
+                .effects
+                .insert(name.clone(), parse_item_effects(item));
+            catalog
+                .types
+                .insert(name.clone(), parse_string_list(item, "type"));
+            catalog
+                .recipes
+                .insert(name.clone(), parse_string_list(item, "recipe"));
+            if let Some(cost) = item.get("buy").and_then(|v| v.as_f64()) {
+                catalog.costs.insert(name.clone(), cost);
+            }
+            let on_summoners_rift = item
+                .get("maps")
+                .and_then(|maps| maps.get("sr"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            if on_summoners_rift {
+                catalog.names.push(name.clone());
+            }
+            if let Some(mythic) = item
+                .get("effects")
+                .and_then(|effects| effects.get("mythic"))
+            {
+                catalog
+                    .mythic_stats
+                    .insert(name.clone(), parse_stat_deltas(mythic));
+            }
+        }
+        catalog.names.sort();
+        return catalog;
+    }
+
+    pub fn stats(&self, name: &str) -> Option<&WikiItemStatDeltas> {
+        return self.stats.get(name);
+    }
+
+    pub fn effects(&self, name: &str) -> Option<&[UnknownItemEffect]> {
+        return self.effects.get(name).map(|effects| effects.as_slice());
+    }
+
+    pub fn mythic_stats(&self, name: &str) -> Option<&WikiItemStatDeltas> {
+        return self.mythic_stats.get(name);
+    }
+
+    /**
+     * Item types, ie "Legendary", empty for unknown items
+     */
+    pub fn types(&self, name: &str) -> &[String] {
+        return self.types.get(name).map_or(&[], |types| types.as_slice());
+    }
+
+    pub fn cost(&self, name: &str) -> Option<f64> {
+        return self.costs.get(name).copied();
+    }
+
+    /**
+     * Items the item is built from, empty for basic and unknown items
+     */
+    pub fn recipe(&self, name: &str) -> &[String] {
+        return self
+            .recipes
+            .get(name)
+            .map_or(&[], |recipe| recipe.as_slice());
+    }
+
+    pub fn names(&self) -> &[String] {
+        return &self.names;
+    }
+}
+
+static WIKI_ITEM_CATALOG: Lazy<WikiItemCatalog> = Lazy::new(WikiItemCatalog::from_wiki_json);
+
+pub fn load_wiki_item_catalog() -> &'static WikiItemCatalog {
+    &WIKI_ITEM_CATALOG
+}
+
+pub fn load_wiki_item_stats(name: &str) -> &'static WikiItemStatDeltas {
+    return load_wiki_item_catalog()
+        .stats(name)
+        .unwrap_or_else(|| panic!("unknown item {}", name));
+}
+
+/**
+ * Names of every item that is a full entry (not an ornn / alias redirect) and is enabled on summoners rift
+ */
+pub fn load_wiki_item_names() -> &'static [String] {
+    return load_wiki_item_catalog().names();
+}
+
+pub fn load_wiki_item_cost(name: &str) -> Option<f64> {
+    return load_wiki_item_catalog().cost(name);
+}
+
+pub fn load_wiki_item_types(name: &str) -> &'static [String] {
+    return load_wiki_item_catalog().types(name);
+}
+
+/**
+ * Items the item is built from, empty for basic items
+ */
+pub fn load_wiki_item_recipe(name: &str) -> &'static [String] {
+    return load_wiki_item_catalog().recipe(name);
+}
+
+pub fn load_wiki_item_effects(name: &str) -> &'static [UnknownItemEffect] {
+    return load_wiki_item_catalog()
+        .effects(name)
+        .unwrap_or_else(|| panic!("unknown item {}", name));
+}
+
+pub fn load_wiki_item_effect(
+    item_name: &str,
+    effect_name: &str,
+) -> Option<&'static UnknownItemEffect> {
+    return load_wiki_item_effects(item_name)
+        .iter()
+        .find(|effect| effect.name == effect_name);
+}
+
+/**
+ * The item's active (ie goredrinker's thirsting slash), None for items without one
+ */
+pub fn load_wiki_item_active(item_name: &str) -> Option<UnknownItemEffect> {
+    let all_items = open_wiki_item_json();
+    return all_items
+        .get(item_name)
+        .and_then(|item| item.get("effects"))
+        .and_then(|effects| effects.get("act"))
+        .and_then(|value| serde_json::from_value(value.to_owned()).ok());
+}
+
+/**
+ * Stats a mythic item grants per legendary item in the build, None for non-mythics
+ */
+pub fn load_wiki_item_mythic_stats(name: &str) -> Option<&'static WikiItemStatDeltas> {
+    return load_wiki_item_catalog().mythic_stats(name);
+}
+
+pub fn is_legendary_item(name: &str) -> bool {
+    load_wiki_item_types(name)
+        .iter()
+        .any(|item_type| item_type == "Legendary")
+}
+
+/**
+ * Applies a full build, which unlike applying items one at a time also grants mythic passive stats for each legendary item
+ */
+pub fn apply_items_to_champ(item_names: &[&str], champion: &mut Champion) {
+    for item_name in item_names {
+        apply_item_to_champ(item_name, champion);
+    }
+    let legendary_count = item_names
+        .iter()
+        .filter(|item_name| is_legendary_item(item_name))
+        .count();
+    for item_name in item_names {
+        if let Some(mythic_stats) = load_wiki_item_mythic_stats(item_name) {
+            mythic_stats
+                .times(legendary_count as f64)
+                .modify_champion_stats(&mut champion.stats);
+        }
+    }
 }
 
 pub fn apply_item_to_champ(item_name: &str, champion: &mut Champion) {
-    let item = load_wiki_item_stats(item_name.to_string());
+    let item = load_wiki_item_stats(item_name);
 
-    let concrete_item_effects: Vec<ConcreteItemEffect> =
-        load_wiki_item_effects(item_name.to_string())
-            .iter()
-            .map(|v| (v, item_name).into())
-            .collect();
+    let concrete_item_effects: Vec<ConcreteItemEffect> = load_wiki_item_effects(item_name)
+        .iter()
+        .map(|v| (v, item_name).into())
+        .collect();
     concrete_item_effects
         .into_iter()
         .for_each(|v| v.apply_to_champ(champion));
     item.modify_champion_stats(&mut champion.stats);
+    champion.items.push(item_name.to_string());
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::target::CrowdControl;
+    use approx::assert_relative_eq;
     use rstest::rstest;
 
     #[rstest]
     fn test_load_item_stats() {
-        let long_sword_stats = load_wiki_item_stats("Long Sword".to_string());
+        let long_sword_stats = load_wiki_item_stats("Long Sword");
         assert_eq!(long_sword_stats.attack_damage.unwrap(), 10.0);
     }
+
+    #[rstest]
+    #[case(&["Duskblade of Draktharr"], 20.0, 0.0)]
+    #[case(&["Duskblade of Draktharr", "Last Whisper"], 20.0, 0.0)] // last whisper isnt legendary
+    #[case(&["Duskblade of Draktharr", "Serylda's Grudge", "Black Cleaver"], 80.0, 10.0)]
+    #[case(&["Serylda's Grudge", "Black Cleaver"], 50.0, 0.0)]
+    fn test_mythic_passive(
+        #[case] item_names: &[&str],
+        #[case] expected_haste: f64,
+        #[case] expected_move_speed: f64,
+    ) {
+        let mut champion = Champion::new_dummy();
+        apply_items_to_champ(item_names, &mut champion);
+        assert_eq!(expected_haste, champion.stats.ability_haste);
+        assert_eq!(expected_move_speed, champion.stats.move_speed);
+    }
+
+    #[rstest]
+    fn test_mythic_passive_penetration_adds_up() {
+        let mut champion = Champion::new_dummy();
+        apply_items_to_champ(
+            &["Divine Sunderer", "Serylda's Grudge", "Black Cleaver"],
+            &mut champion,
+        );
+        let mut grudge_and_cleaver = Champion::new_dummy();
+        apply_items_to_champ(
+            &["Serylda's Grudge", "Black Cleaver"],
+            &mut grudge_and_cleaver,
+        );
+        // 3% armor pen per legendary is 6%, not 5.91% from stacking each copy
+        let expected =
+            stack_multiplicative_reduction(6.0, grudge_and_cleaver.stats.percent_armor_pen);
+        assert_relative_eq!(expected, champion.stats.percent_armor_pen);
+        assert_relative_eq!(6.0, champion.stats.percent_magic_pen);
+    }
+
+    #[rstest]
+    fn test_heartsteel_mythic_percent_health() {
+        let mut champion = Champion::new_dummy();
+        apply_items_to_champ(
+            &["Heartsteel", "Warmog's Armor", "Sunfire Aegis"],
+            &mut champion,
+        );
+        assert_relative_eq!(2.0, champion.stats.percent_health);
+        let mut without_passive = Champion::new_dummy();
+        for item_name in ["Heartsteel", "Warmog's Armor", "Sunfire Aegis"] {
+            apply_item_to_champ(item_name, &mut without_passive);
+        }
+        assert_relative_eq!(
+            1.02 * without_passive.get_max_health(),
+            champion.get_max_health()
+        );
+    }
+
+    #[rstest]
+    fn test_catalog_lookups() {
+        let catalog = load_wiki_item_catalog();
+        assert!(std::ptr::eq(
+            catalog.stats("Long Sword").unwrap(),
+            load_wiki_item_stats("Long Sword")
+        ));
+        assert!(catalog.mythic_stats("Long Sword").is_none());
+        assert!(catalog.mythic_stats("Iceborn Gauntlet").is_some());
+        assert!(catalog.effects("Long Swrod").is_none());
+        // ornn upgrades share the passives of their base item
+        let names = |item_name| {
+            let mut names: Vec<&str> = load_wiki_item_effects(item_name)
+                .iter()
+                .map(|effect| effect.name.as_str())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(names("Trinity Force"), names("Infinity Force"));
+    }
+
+    #[rstest]
+    fn test_load_item_cost_and_types() {
+        assert_eq!(load_wiki_item_cost("Long Sword"), Some(350.0));
+        assert_eq!(load_wiki_item_types("Long Sword"), ["Basic"]);
+        let names = load_wiki_item_names();
+        assert!(names.contains(&"Long Sword".to_string()));
+        assert!(!names.contains(&"Infinity Force".to_string()));
+        assert!(std::ptr::eq(names, load_wiki_item_names()));
+        assert_eq!(
+            load_wiki_item_recipe("Caulfield's Warhammer"),
+            ["Long Sword", "Long Sword"]
+        );
+        assert!(load_wiki_item_recipe("Long Sword").is_empty());
+    }
+
+    #[rstest]
+    #[case("+30% [[tenacity]]", "[[tenacity]]", Some(30.0))]
+    #[case("+25% [[slow resist]]", "[[tenacity]]", None)]
+    #[case(
+        "+20% damage against non-epic [[monster]]s",
+        "damage against non-epic [[monster]]s",
+        Some(20.0)
+    )]
+    fn test_parse_spec_percent(
+        #[case] spec: &str,
+        #[case] stat: &str,
+        #[case] expected: Option<f64>,
+    ) {
+        assert_eq!(expected, parse_spec_percent(spec, stat));
+    }
+
+    #[rstest]
+    fn test_tenacity_shortens_crowd_control() {
+        let mut champion = Champion::new_dummy();
+        apply_item_to_champ("Mercury's Treads", &mut champion);
+        assert_eq!(30.0, champion.stat_sheet().tenacity);
+        champion.apply_crowd_control("Stun", CrowdControl::Stun, 1.0);
+        assert_relative_eq!(0.7, champion.crowd_control_remaining());
+    }
 }
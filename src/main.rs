@@ -1,22 +1,51 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+#![allow(clippy::needless_return)]
+use std::{env, process};
 
 use practice_tooled::{
     attack::{self},
     champions::{
-        champion::{Champion, NamedClosures},
+        champion::{AbilityName, CastingData, Champion, NamedClosures},
         leblanc::Leblanc,
+        skill_order::{Skill, SkillOrder},
         Vi,
     },
+    geometry::Position,
+    gold_efficiency::item_gold_efficiency,
+    item_effects::ChampionApplyable,
+    jungle::JungleClear,
     load_champion::{load_champion_names, load_champion_stats, ChampionStatModifier},
     load_dd_item::load_dd_item,
-    load_wiki_item::{apply_item_to_champ, load_wiki_item_stats, open_wiki_item_json},
+    load_wiki_item::{apply_items_to_champ, load_wiki_item_stats, open_wiki_item_json},
+    runes::{apply_runes_to_champ, Rune, StatShard},
+    scenario::run_scenario_files,
+    summoner_spells::SummonerSpell,
+    sweep::{evaluate_batch, Combatant, Combo, Scenario, ScenarioResult},
     target::VitalityData,
+    timeline::{IncomeModel, Timeline},
+    world::World,
 };
 
 fn main() {
+    // scenario files given as arguments replace the examples
+    let scenario_paths: Vec<String> = env::args().skip(1).collect();
+    if !scenario_paths.is_empty() {
+        match run_scenario_files(&scenario_paths) {
+            Ok(reports) => reports.iter().for_each(|report| println!("{}", report)),
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
     open_wiki_item_json();
-    load_wiki_item_stats("Long Sword".to_string());
+    load_wiki_item_stats("Long Sword");
     example_vi_ult_combo(Vec::from(["Duskblade of Draktharr"]));
+    example_vi_ult_combo(Vec::from([
+        "Duskblade of Draktharr",
+        "Serylda's Grudge",
+        "Black Cleaver",
+    ]));
     example_vi_ult_combo(Vec::from(["Spear of Shojin"]));
     example_vi_ult_combo(Vec::from(["Essence Reaver"]));
     example_vi_ult_combo(Vec::from(["Umbral Glaive"]));
@@ -27,49 +56,305 @@ fn main() {
         "Long Sword",
         "Long Sword",
     ]));
-    // for _ in 1..1_000_000 {
-    //     example_vi_ult_combo();
-    // }
+    let shards = [
+        Rune::StatShard(StatShard::AdaptiveForce),
+        Rune::StatShard(StatShard::AdaptiveForce),
+        Rune::StatShard(StatShard::Armor),
+    ];
+    example_vi_ult_combo_with_runes(
+        Vec::from(["Duskblade of Draktharr"]),
+        [&[Rune::Electrocute, Rune::SuddenImpact][..], &shards].concat(),
+    );
+    example_vi_ult_combo_with_runes(
+        Vec::from(["Duskblade of Draktharr"]),
+        [&[Rune::Conqueror, Rune::CoupDeGrace][..], &shards].concat(),
+    );
+    example_vi_ult_combo_with_summoners(
+        Vec::from(["Duskblade of Draktharr"]),
+        &[SummonerSpell::Flash, SummonerSpell::Ignite],
+    );
+    example_vi_ult_combo_with_summoners(
+        Vec::from(["Duskblade of Draktharr"]),
+        &[SummonerSpell::Flash, SummonerSpell::ChillingSmite],
+    );
+    example_vi_power_curve(Vec::from([
+        "Long Sword",
+        "Serrated Dirk",
+        "Duskblade of Draktharr",
+        "Black Cleaver",
+    ]));
+    example_vi_stat_sheet(Vec::from(["Duskblade of Draktharr", "Black Cleaver"]), 11);
+    example_vi_jungle_clear(Vec::from(["Long Sword"]), Vec::from([Rune::Conqueror]));
+    example_vi_chase_leblanc(Vec::from(["Boots"]), 1000.0);
+    example_vi_chase_leblanc(Vec::from(["Plated Steelcaps"]), 1000.0);
+    example_gold_efficiency(Vec::from([
+        "Serrated Dirk",
+        "Caulfield's Warhammer",
+        "Duskblade of Draktharr",
+        "Black Cleaver",
+    ]));
+    example_vi_build_sweep(&[
+        "Long Sword",
+        "Serrated Dirk",
+        "Duskblade of Draktharr",
+        "Black Cleaver",
+        "Serylda's Grudge",
+        "Last Whisper",
+    ]);
 }
 
 #[allow(dead_code)]
 fn example_vi_ult_combo(item_names: Vec<&str>) {
+    example_vi_ult_combo_with_runes(item_names, Vec::new());
+}
+
+#[allow(dead_code)]
+fn example_vi_ult_combo_with_runes(item_names: Vec<&str>, runes: Vec<Rune>) {
     let level = 6;
-    let empty_closures = NamedClosures {
-        data: HashMap::new(),
-    };
-    let mut leblanc = Champion::new(
+    let empty_closures = NamedClosures::default();
+    let mut world = World::new();
+    let leblanc = world.spawn(Champion::new(
         Leblanc::NAME.to_string(),
         level,
         [0, 0, 0, 0],
         empty_closures,
-    );
+    ));
 
     let mut vi_data = Vi::new();
 
     let vi_closures = vi_data.get_name_closures();
-    let vi = Rc::new(RefCell::new(Champion::new(
+    let mut vi = Champion::new_with_skill_order(
         Vi::NAME.to_string(),
         level,
-        [0, 0, 2, 0],
+        SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W]),
         vi_closures,
-    )));
+    );
 
-    for item_name in &item_names {
-        apply_item_to_champ(item_name, &mut vi.borrow_mut());
-    }
+    apply_items_to_champ(&item_names, &mut vi);
+    apply_runes_to_champ(&runes, &mut vi);
 
-    let ranks = vi.borrow().ranks.clone();
-    Champion::execute_combo(Rc::clone(&vi), Vi::ult_combo(ranks), &mut leblanc);
+    let vi = world.spawn(vi);
+    world.execute_combo(vi, Vi::ult_combo(), leblanc);
+    let leblanc = &world[leblanc];
 
     println!(
-        "Full combo with items \x1b[93m{:?}\x1b[0m deals \x1b[93m{:.2}\x1b[0m out of {:.2} hp against a target with {} armor",
+        "Full combo with items \x1b[93m{:?}\x1b[0m and runes {:?} deals \x1b[93m{:.2}\x1b[0m out of {:.2} hp against a target with {} armor",
         item_names,
+        runes,
         leblanc.get_missing_health(),
         leblanc.get_max_health(),
         leblanc.get_base_armor() + leblanc.get_bonus_armor(),
     );
 }
+#[allow(dead_code)]
+fn example_vi_ult_combo_with_summoners(item_names: Vec<&str>, spells: &[SummonerSpell]) {
+    let level = 6;
+    let mut world = World::new();
+    let leblanc = world.spawn(Champion::new(
+        Leblanc::NAME.to_string(),
+        level,
+        [0, 0, 0, 0],
+        NamedClosures::default(),
+    ));
+    let mut vi = Champion::new(
+        Vi::NAME.to_string(),
+        level,
+        [1, 1, 3, 1],
+        Vi::new().get_name_closures(),
+    );
+    apply_items_to_champ(&item_names, &mut vi);
+
+    let combo = Vi::ult_combo_with_summoners(spells);
+    let vi = world.spawn(vi);
+    world.execute_combo(vi, combo, leblanc);
+    // let burns run out
+    world.advance_time(5.0);
+    let leblanc = &world[leblanc];
+
+    println!(
+        "Full combo with items \x1b[93m{:?}\x1b[0m and summoners {:?} deals \x1b[93m{:.2}\x1b[0m out of {:.2} hp",
+        item_names,
+        spells,
+        leblanc.get_missing_health(),
+        leblanc.get_max_health(),
+    );
+}
+
+#[allow(dead_code)]
+fn example_vi_power_curve(purchase_order: Vec<&str>) {
+    let timeline = Timeline::new(IncomeModel::solo_lane(0.7), &purchase_order);
+    let minutes: Vec<f64> = (0..=30)
+        .step_by(5)
+        .map(|minute| 60.0 * minute as f64)
+        .collect();
+    let curve = timeline.evaluate(&minutes, |snapshot| {
+        let mut world = World::new();
+        let leblanc = world.spawn(Champion::new(
+            Leblanc::NAME.to_string(),
+            snapshot.level,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        ));
+        let mut vi = Champion::new_with_skill_order(
+            Vi::NAME.to_string(),
+            1,
+            SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W]),
+            Vi::new().get_name_closures(),
+        );
+        snapshot.apply_to_champ(&mut vi);
+        let vi = world.spawn(vi);
+        world.execute_combo(vi, Vi::ult_combo(), leblanc);
+        world[leblanc].get_missing_health() / world[leblanc].get_max_health()
+    });
+    for (snapshot, damage_share) in curve {
+        println!(
+            "{:>2} min \t level {:>2} \t items {:?} \t ult combo deals \x1b[93m{:.0}%\x1b[0m of an even leblanc's hp",
+            snapshot.time / 60.0,
+            snapshot.level,
+            snapshot.items,
+            damage_share * 100.0,
+        );
+    }
+}
+
+/**
+ * Every pair of the candidate items, evaluated in parallel
+ */
+#[allow(dead_code)]
+fn example_vi_build_sweep(candidates: &[&str]) {
+    let level = 11;
+    let vi = Combatant::Champion {
+        name: Vi::NAME.to_string(),
+        level,
+        skill_order: Some(SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W])),
+        items: Vec::new(),
+        runes: Vec::new(),
+    };
+    let scenarios: Vec<Scenario> = candidates
+        .iter()
+        .enumerate()
+        .flat_map(|(index, first)| {
+            candidates[index + 1..].iter().map(|second| Scenario {
+                attacker: vi.clone(),
+                build: vec![first.to_string(), second.to_string()],
+                target: Combatant::champion(Leblanc::NAME, level),
+                combo: Combo::Rotation(Vi::ult_combo),
+            })
+        })
+        .collect();
+    let mut results: Vec<(&Scenario, ScenarioResult)> =
+        scenarios.iter().zip(evaluate_batch(&scenarios)).collect();
+    results.sort_by(|(_, left), (_, right)| right.damage.total_cmp(&left.damage));
+    for (scenario, result) in results.iter().take(5) {
+        println!(
+            "Build \x1b[93m{:?}\x1b[0m ult combo deals \x1b[93m{:.0}%\x1b[0m of an even leblanc's hp",
+            scenario.build,
+            result.damage_share() * 100.0,
+        );
+    }
+}
+
+#[allow(dead_code)]
+fn example_vi_stat_sheet(item_names: Vec<&str>, level: u8) {
+    let mut vi = Champion::new(
+        Vi::NAME.to_string(),
+        level,
+        [0, 0, 0, 0],
+        Vi::new().get_name_closures(),
+    );
+    apply_items_to_champ(&item_names, &mut vi);
+    println!(
+        "Vi with items \x1b[93m{:?}\x1b[0m\n{}",
+        item_names,
+        vi.stat_sheet()
+    );
+}
+
+#[allow(dead_code)]
+fn example_vi_jungle_clear(item_names: Vec<&str>, runes: Vec<Rune>) {
+    let mut vi = Champion::new_with_skill_order(
+        Vi::NAME.to_string(),
+        1,
+        SkillOrder::max_by_priority([Skill::Q, Skill::E, Skill::W]),
+        Vi::new().get_name_closures(),
+    );
+    apply_items_to_champ(&item_names, &mut vi);
+    apply_runes_to_champ(&runes, &mut vi);
+    let clear = JungleClear::new(
+        &["Red", "Krugs", "Raptors", "Wolves", "Blue", "Gromp"],
+        Vi::jungle_rotation,
+    );
+    let result = clear.run(vi);
+    for camp in &result.camps {
+        println!(
+            "{:>8} \t at {:>6.1}s \t took \x1b[93m{:>5.1}s\x1b[0m \t {:>4.0} hp left \t level {}",
+            camp.camp, camp.start, camp.duration, camp.health, camp.level
+        );
+    }
+    println!(
+        "Full clear with items \x1b[93m{:?}\x1b[0m and runes {:?} takes \x1b[93m{:.1}s\x1b[0m, {:.0} hp left{}",
+        item_names,
+        runes,
+        result.total_time,
+        result.remaining_health,
+        if result.died { " (died)" } else { "" },
+    );
+}
+
+#[allow(dead_code)]
+fn example_vi_chase_leblanc(item_names: Vec<&str>, gap: f64) {
+    let level = 6;
+    let mut vi = Champion::new(
+        Vi::NAME.to_string(),
+        level,
+        [1, 1, 1, 1],
+        Vi::new().get_name_closures(),
+    );
+    apply_items_to_champ(&item_names, &mut vi);
+    let mut leblanc = Champion::new(
+        Leblanc::NAME.to_string(),
+        level,
+        [0, 0, 0, 0],
+        NamedClosures::default(),
+    );
+    leblanc.position = Position::new(gap, 0.0);
+    // a fully charged q covers the last stretch
+    let q_range = vi
+        .cast_range(
+            &AbilityName::Q,
+            &CastingData {
+                charge: 1.25,
+                ..Default::default()
+            },
+        )
+        .unwrap_or(0.0);
+    match vi.time_to_catch(&leblanc, q_range) {
+        Some(seconds) => println!(
+            "Vi with items \x1b[93m{:?}\x1b[0m gets a charged q on a fleeing leblanc {} units away after \x1b[93m{:.2}s\x1b[0m",
+            item_names, gap, seconds
+        ),
+        None => println!(
+            "Vi with items \x1b[93m{:?}\x1b[0m never catches a fleeing leblanc {} units away",
+            item_names, gap
+        ),
+    }
+}
+
+#[allow(dead_code)]
+fn example_gold_efficiency(item_names: Vec<&str>) {
+    for item_name in item_names {
+        let efficiency = item_gold_efficiency(item_name).unwrap();
+        println!(
+            "Item \x1b[93m{}\x1b[0m costs {:.0} and grants {:.2} gold of stats, \x1b[93m{:.2}%\x1b[0m gold efficient (unpriced stats: {:?})",
+            item_name,
+            efficiency.cost,
+            efficiency.gold_value,
+            efficiency.efficiency() * 100.0,
+            efficiency.unpriced_stats,
+        );
+    }
+}
+
 #[allow(dead_code)]
 fn example_vi_staring_item() {
     let level = 2;
@@ -100,15 +385,15 @@ fn example_vi_staring_item() {
             None,
         );
         println!(
-            "champion: {} \t\t level: {} \t item: {} \t dps: {:.2}",
-            "vi", level, item_name, dps
+            "champion: vi \t\t level: {} \t item: {} \t dps: {:.2}",
+            level, item_name, dps
         )
     }
 }
 
 #[allow(dead_code)]
 fn example_basic_attack_dps() {
-    // known issue: doesnt accomodate for non-one ad scaling (kalista) or on hit passives
+    // known issue: doesnt accomodate for on hit passives
     let target = VitalityData {
         base_armor: 30f64,
         ..Default::default()
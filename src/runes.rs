@@ -0,0 +1,428 @@
+use serde::Deserialize;
+
+use crate::{
+    champions::champion::{AbilityFn, AbilityName, CastingData, Champion},
+    core::linear_by_level,
+    item_effects::ChampionApplyable,
+    stat_sheet::HitSheets,
+    target::{
+        AbilityEffect, DamageType, EffectData, EffectResult, StackApplyInfo, StackCounter,
+        StackReset, StatBuff,
+    },
+    time_manager::TIME,
+    world::{EntityId, World},
+};
+
+const ELECTROCUTE: &str = "Electrocute";
+const PRESS_THE_ATTACK: &str = "Press the Attack";
+const PRESS_THE_ATTACK_EXPOSED: &str = "Press the Attack Exposed";
+const CONQUEROR: &str = "Conqueror";
+const LETHAL_TEMPO: &str = "Lethal Tempo";
+const FIRST_STRIKE: &str = "First Strike";
+const FIRST_STRIKE_COOLDOWN: &str = "First Strike Cooldown";
+const SUDDEN_IMPACT: &str = "Sudden Impact";
+
+const CONQUEROR_MAX_STACKS: u8 = 12;
+const LETHAL_TEMPO_MAX_STACKS: u8 = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum StatShard {
+    AdaptiveForce,
+    AttackSpeed,
+    AbilityHaste,
+    Armor,
+    MagicResist,
+    ScalingHealth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Rune {
+    Electrocute,
+    Conqueror,
+    PressTheAttack,
+    LethalTempo,
+    FirstStrike,
+    SuddenImpact,
+    CoupDeGrace,
+    CutDown,
+    LegendAlacrity(u8), // stacks, capped at 10
+    StatShard(StatShard),
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub struct AdaptiveBonus {
+    pub attack_damage: f64,
+    pub ability_power: f64,
+}
+
+impl AdaptiveBonus {
+    /**
+     * Adaptive force goes to whichever of bonus ad / ap the champion has more of, favouring ad
+     */
+    pub fn new(champion: &Champion, amount_ad: f64, amount_ap: f64) -> AdaptiveBonus {
+        if is_adaptive_physical(champion) {
+            AdaptiveBonus {
+                attack_damage: amount_ad,
+                ability_power: 0.0,
+            }
+        } else {
+            AdaptiveBonus {
+                attack_damage: 0.0,
+                ability_power: amount_ap,
+            }
+        }
+    }
+}
+
+fn is_adaptive_physical(champion: &Champion) -> bool {
+    champion.stats.bonus_attack_damage >= champion.stats.ability_power
+}
+
+pub fn adaptive_damage_type(champion: &Champion) -> DamageType {
+    if is_adaptive_physical(champion) {
+        DamageType::Physical
+    } else {
+        DamageType::Magic
+    }
+}
+
+/**
+ * Stat shards and legend runes are flat stats, so should be applied after items for adaptive force to pick correctly
+ */
+impl ChampionApplyable for Rune {
+    fn apply_to_champ(self, champion: &mut Champion) {
+        match self {
+            Rune::LegendAlacrity(stacks) => {
+                champion.stats.bonus_attack_speed += 3.0 + 1.5 * stacks.min(10) as f64
+            }
+            Rune::StatShard(shard) => shard.apply_to_champ(champion),
+            _ => {}
+        }
+        champion.runes.push(self);
+    }
+}
+
+impl ChampionApplyable for StatShard {
+    fn apply_to_champ(self, champion: &mut Champion) {
+        match self {
+            StatShard::AdaptiveForce => {
+                let bonus = AdaptiveBonus::new(champion, 5.4, 9.0);
+                champion.stats.bonus_attack_damage += bonus.attack_damage;
+                champion.stats.ability_power += bonus.ability_power;
+            }
+            StatShard::AttackSpeed => champion.stats.bonus_attack_speed += 10.0,
+            StatShard::AbilityHaste => champion.stats.ability_haste += 8.0,
+            StatShard::Armor => champion.stats.armor += 6.0,
+            StatShard::MagicResist => champion.stats.magic_resist += 8.0,
+            StatShard::ScalingHealth => {
+                champion.stats.health += linear_by_level(15.0, 140.0, champion.level)
+            }
+        }
+    }
+}
+
+pub fn apply_runes_to_champ(runes: &[Rune], champion: &mut Champion) {
+    runes.iter().for_each(|rune| rune.apply_to_champ(champion));
+}
+
+/**
+ * Called for each auto / ability cast, after its damage is dealt
+ */
+pub fn process_rune_hooks(
+    world: &mut World,
+    attacker_id: EntityId,
+    name: &AbilityName,
+    target_id: EntityId,
+) {
+    let runes = world[attacker_id].runes.clone();
+    let is_auto = matches!(name, AbilityName::AUTO);
+    let is_ranged = world[attacker_id].is_ranged();
+    for rune in runes {
+        match rune {
+            Rune::Electrocute if !world[attacker_id].is_on_cooldown(ELECTROCUTE) => {
+                StackCounter::on_nth_hit(
+                    3,
+                    StackApplyInfo {
+                        unique_name: ELECTROCUTE.to_string(),
+                        result: Box::new(EffectResult::AbilityEffect(AbilityEffect {
+                            attacker: attacker_id,
+                            name: AbilityName::Electrocute,
+                            data: CastingData::default(),
+                        })),
+                        ttl: 0.0,
+                    },
+                    3.0,
+                )
+                .upsert_to_champ(&mut world[target_id], ELECTROCUTE);
+            }
+            Rune::PressTheAttack if is_auto && !is_exposed(&world[target_id]) => {
+                StackCounter::on_nth_hit(
+                    3,
+                    StackApplyInfo {
+                        unique_name: PRESS_THE_ATTACK.to_string(),
+                        result: Box::new(EffectResult::AbilityEffect(AbilityEffect {
+                            attacker: attacker_id,
+                            name: AbilityName::PressTheAttack,
+                            data: CastingData::default(),
+                        })),
+                        ttl: 0.0,
+                    },
+                    4.0,
+                )
+                .upsert_to_champ(&mut world[target_id], PRESS_THE_ATTACK);
+                // the proc lands before the target is exposed
+                world.fire_triggered_abilities(target_id);
+                StackCounter::on_nth_hit(
+                    3,
+                    StackApplyInfo {
+                        unique_name: PRESS_THE_ATTACK_EXPOSED.to_string(),
+                        result: Box::new(EffectResult::IncomingDamageAmplifier(8.0)),
+                        ttl: 6.0,
+                    },
+                    4.0,
+                )
+                .upsert_to_champ(&mut world[target_id], PRESS_THE_ATTACK_EXPOSED);
+            }
+            Rune::Conqueror => {
+                let increment = if is_auto && is_ranged { 1 } else { 2 };
+                let attacker = &mut world[attacker_id];
+                let per_stack = AdaptiveBonus::new(
+                    attacker,
+                    linear_by_level(1.2, 2.7, attacker.level),
+                    linear_by_level(2.0, 4.5, attacker.level),
+                );
+                StackCounter {
+                    increment,
+                    max_stacks: CONQUEROR_MAX_STACKS,
+                    ttl: 5.0,
+                    per_stack: Some(Box::new(EffectResult::StatBuff(StatBuff {
+                        attack_damage: per_stack.attack_damage,
+                        ability_power: per_stack.ability_power,
+                        ..Default::default()
+                    }))),
+                    reset: StackReset::Never,
+                    ..Default::default()
+                }
+                .upsert_to_champ(attacker, CONQUEROR);
+            }
+            Rune::LethalTempo if is_auto => {
+                let attacker = &mut world[attacker_id];
+                let per_stack = if is_ranged {
+                    linear_by_level(3.6, 7.2, attacker.level)
+                } else {
+                    linear_by_level(5.0, 9.0, attacker.level)
+                };
+                StackCounter {
+                    increment: 1,
+                    max_stacks: LETHAL_TEMPO_MAX_STACKS,
+                    ttl: 6.0,
+                    per_stack: Some(Box::new(EffectResult::StatBuff(StatBuff {
+                        attack_speed: per_stack,
+                        ..Default::default()
+                    }))),
+                    reset: StackReset::Never,
+                    ..Default::default()
+                }
+                .upsert_to_champ(attacker, LETHAL_TEMPO);
+            }
+            Rune::FirstStrike => {
+                let attacker = &mut world[attacker_id];
+                if !attacker.is_on_cooldown(FIRST_STRIKE_COOLDOWN) {
+                    attacker.upsert_effect(EffectData {
+                        unique_name: FIRST_STRIKE.to_string(),
+                        expiry: TIME.with(|time| *time.borrow() + 3.0),
+                        result: EffectResult::OutgoingDamageAmplifier(7.0),
+                    });
+                    let cooldown = linear_by_level(25.0, 15.0, attacker.level);
+                    attacker.put_on_cooldown(FIRST_STRIKE_COOLDOWN, cooldown);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_exposed(target: &Champion) -> bool {
+    target.valid_effects().any(|effect| {
+        effect.unique_name == PRESS_THE_ATTACK_EXPOSED
+            && matches!(effect.result, EffectResult::IncomingDamageAmplifier(_))
+    })
+}
+
+/**
+ * Lethal tempo at full stacks lets attack speed go past the cap
+ */
+pub fn lifts_attack_speed_cap(champion: &Champion) -> bool {
+    champion.runes.contains(&Rune::LethalTempo)
+        && champion.get_stacks(LETHAL_TEMPO) >= LETHAL_TEMPO_MAX_STACKS
+}
+
+/**
+ * Called by abilities that dash, blink or leap (ie vi q / r)
+ */
+pub fn on_dash(champion: &mut Champion) {
+    if !champion.runes.contains(&Rune::SuddenImpact) || champion.is_on_cooldown(SUDDEN_IMPACT) {
+        return;
+    }
+    champion.upsert_effect(EffectData {
+        unique_name: SUDDEN_IMPACT.to_string(),
+        expiry: TIME.with(|time| *time.borrow() + 5.0),
+        result: EffectResult::Penetration {
+            lethality: 9.0,
+            flat_magic_pen: 7.0,
+        },
+    });
+    champion.put_on_cooldown(SUDDEN_IMPACT, 10.0);
+}
+
+/**
+ * Percent increased damage the attacker's runes grant against this target
+ */
+pub fn rune_damage_amplification(
+    attacker: &Champion,
+    target: &Champion,
+    sheets: &HitSheets,
+) -> f64 {
+    let mut percent = 0.0;
+    let target_max_health = sheets.target.health.total();
+    for rune in &attacker.runes {
+        match rune {
+            Rune::CoupDeGrace if target.current_health < 0.4 * target_max_health => {
+                percent += 8.0;
+            }
+            Rune::CutDown => {
+                // 5% at 10% more max health than the attacker, up to 15% at double
+                let difference = target_max_health / sheets.attacker.health.total() - 1.0;
+                if difference >= 0.1 {
+                    percent += 5.0 + 10.0 * (difference.min(1.0) - 0.1) / 0.9;
+                }
+            }
+            _ => {}
+        }
+    }
+    return percent;
+}
+
+fn electrocute() -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+    return move |world: &mut World,
+                 attacker: EntityId,
+                 target: EntityId,
+                 _casting_data: &CastingData| {
+        let sheets = HitSheets::new(&world[attacker], &world[target]);
+        let (damage, damage_type, cooldown) = {
+            let attacker = &world[attacker];
+            let damage = linear_by_level(30.0, 220.0, attacker.level)
+                + 0.4 * sheets.attacker.attack_damage.bonus
+                + 0.25 * sheets.attacker.ability_power.total();
+            let cooldown = linear_by_level(25.0, 20.0, attacker.level);
+            (damage, adaptive_damage_type(attacker), cooldown)
+        };
+        world.deal_typed_damage_with(attacker, target, &sheets, damage, damage_type);
+        world[attacker].put_on_cooldown(ELECTROCUTE, cooldown);
+    };
+}
+
+fn press_the_attack() -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+    return move |world: &mut World,
+                 attacker: EntityId,
+                 target: EntityId,
+                 _casting_data: &CastingData| {
+        let damage = linear_by_level(40.0, 180.0, world[attacker].level);
+        let damage_type = adaptive_damage_type(&world[attacker]);
+        world.deal_typed_damage(attacker, target, damage, damage_type);
+    };
+}
+
+pub fn rune_abilities() -> Vec<(AbilityName, Box<AbilityFn>)> {
+    return vec![
+        (AbilityName::Electrocute, Box::new(electrocute())),
+        (AbilityName::PressTheAttack, Box::new(press_the_attack())),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attack::AttackSpeed;
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    fn execute_autos(world: &mut World, attacker: EntityId, target: EntityId, count: usize) {
+        for _ in 0..count {
+            world.execute_ability(
+                attacker,
+                &AbilityName::AUTO,
+                target,
+                &CastingData::default(),
+            );
+            TIME.with(|time| *time.borrow_mut() += 0.5);
+        }
+    }
+
+    fn dummy_with_runes(runes: &[Rune]) -> (World, EntityId, EntityId) {
+        let mut attacker = Champion::new_dummy();
+        attacker.stats.base_attack_damage = 100.0;
+        apply_runes_to_champ(runes, &mut attacker);
+        let mut world = World::new();
+        let attacker = world.spawn(attacker);
+        let target = world.spawn(Champion::new_dummy());
+        (world, attacker, target)
+    }
+
+    #[rstest]
+    #[case(Rune::Electrocute, 30.0)]
+    #[case(Rune::PressTheAttack, 40.0)]
+    fn test_keystone_procs_on_third_hit(#[case] rune: Rune, #[case] expected_proc: f64) {
+        let (mut world, attacker, target) = dummy_with_runes(&[rune]);
+        execute_autos(&mut world, attacker, target, 2);
+        assert_relative_eq!(200.0, world[target].get_missing_health());
+        execute_autos(&mut world, attacker, target, 1);
+        assert_relative_eq!(300.0 + expected_proc, world[target].get_missing_health());
+    }
+
+    #[rstest]
+    fn test_conqueror_stacks() {
+        let (mut world, attacker, target) = dummy_with_runes(&[Rune::Conqueror]);
+        execute_autos(&mut world, attacker, target, 3);
+        assert_eq!(6, world[attacker].get_stacks(CONQUEROR));
+        execute_autos(&mut world, attacker, target, 10);
+        assert_eq!(CONQUEROR_MAX_STACKS, world[attacker].get_stacks(CONQUEROR));
+        assert_relative_eq!(12.0 * 1.2, world[attacker].get_bonus_ad());
+
+        TIME.with(|time| *time.borrow_mut() += 10.0);
+        assert_relative_eq!(0.0, world[attacker].get_bonus_ad());
+    }
+
+    #[rstest]
+    #[case(1000.0, 8.0)]
+    #[case(300.0, 0.0)]
+    fn test_coup_de_grace(#[case] missing_health: f64, #[case] expected: f64) {
+        let (mut world, attacker, target) = dummy_with_runes(&[Rune::CoupDeGrace]);
+        world[target].current_health -= missing_health.min(700.0);
+        assert_relative_eq!(
+            expected,
+            rune_damage_amplification(
+                &world[attacker],
+                &world[target],
+                &HitSheets::new(&world[attacker], &world[target])
+            )
+        );
+    }
+
+    #[rstest]
+    fn test_lethal_tempo_lifts_attack_speed_cap() {
+        let (mut world, attacker, target) = dummy_with_runes(&[Rune::LethalTempo]);
+        world[attacker].stats.attack_speed = 1.0;
+        world[attacker].stats.bonus_attack_speed = 200.0;
+        let capped = world[attacker].get_attack_speed().get_attacks_per_second();
+        assert_relative_eq!(AttackSpeed::CAP, capped);
+        execute_autos(
+            &mut world,
+            attacker,
+            target,
+            LETHAL_TEMPO_MAX_STACKS as usize,
+        );
+        let uncapped = world[attacker].get_attack_speed().get_attacks_per_second();
+        assert!(uncapped > AttackSpeed::CAP);
+    }
+}
@@ -0,0 +1,330 @@
+use std::{fmt, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    champions::{champion::AbilityName, champion_closures, champion_rotation},
+    item_effects::STATIC_ABILITIES,
+    load_champion::load_champion_catalog,
+    load_wiki_item::open_wiki_item_json,
+    sweep::{evaluate_batch, Combatant, Combo, ComboStep, Scenario, ScenarioResult},
+};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    Damage,
+    DamageShare,
+    RemainingHealth,
+    Duration,
+}
+
+impl Metric {
+    pub fn value(&self, result: &ScenarioResult) -> f64 {
+        return match self {
+            Metric::Damage => result.damage,
+            Metric::DamageShare => result.damage_share(),
+            Metric::RemainingHealth => result.target_max_health - result.damage,
+            Metric::Duration => result.duration,
+        };
+    }
+}
+
+/**
+ * A rotation of the attacker by name (ie "ult_combo"), or the abilities to cast in order
+ */
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum ComboData {
+    Rotation(String),
+    Steps(Vec<ComboStep>),
+}
+
+fn default_metrics() -> Vec<Metric> {
+    vec![Metric::Damage, Metric::DamageShare]
+}
+
+/**
+ * Declarative form of a `Scenario`, as kept in the json files of `scenarios/`
+ */
+#[derive(Deserialize, Debug, Clone)]
+pub struct ScenarioFile {
+    pub name: String,
+    pub attacker: Combatant,
+    pub target: Combatant,
+    pub combo: ComboData,
+    #[serde(default = "default_metrics")]
+    pub metrics: Vec<Metric>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScenarioError {
+    Read {
+        path: String,
+        message: String,
+    },
+    Parse {
+        path: String,
+        message: String,
+    },
+    UnknownChampion(String),
+    UnknownItem(String),
+    UnknownRotation {
+        champion: String,
+        rotation: String,
+    },
+    UnknownAbility {
+        champion: String,
+        ability: AbilityName,
+    },
+}
+
+impl fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScenarioError::Read { path, message } => {
+                write!(f, "could not read {}: {}", path, message)
+            }
+            ScenarioError::Parse { path, message } => {
+                write!(f, "could not parse {}: {}", path, message)
+            }
+            ScenarioError::UnknownChampion(name) => write!(f, "unknown champion {}", name),
+            ScenarioError::UnknownItem(name) => write!(f, "unknown item {}", name),
+            ScenarioError::UnknownRotation { champion, rotation } => {
+                write!(f, "{} has no rotation named {}", champion, rotation)
+            }
+            ScenarioError::UnknownAbility { champion, ability } => {
+                write!(f, "{} has no ability {:?}", champion, ability)
+            }
+        }
+    }
+}
+
+impl ScenarioFile {
+    pub fn load(path: &Path) -> Result<ScenarioFile, ScenarioError> {
+        let contents = fs::read_to_string(path).map_err(|error| ScenarioError::Read {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        })?;
+        return serde_json::from_str(&contents).map_err(|error| ScenarioError::Parse {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        });
+    }
+
+    /**
+     * Checks every champion, item, rotation and ability name up front, the loaders panic on unknown ones
+     */
+    pub fn to_scenario(&self) -> Result<Scenario, ScenarioError> {
+        validate_combatant(&self.attacker)?;
+        validate_combatant(&self.target)?;
+        let champion = match &self.attacker {
+            Combatant::Champion { name, .. } => name.as_str(),
+            Combatant::Dummy { .. } => "dummy",
+        };
+        let combo = match &self.combo {
+            ComboData::Steps(steps) => {
+                let closures = champion_closures(champion);
+                let unknown = steps.iter().find(|step| {
+                    !closures.data.contains_key(&step.ability)
+                        && !STATIC_ABILITIES.contains_key(&step.ability)
+                });
+                if let Some(step) = unknown {
+                    return Err(ScenarioError::UnknownAbility {
+                        champion: champion.to_string(),
+                        ability: step.ability.clone(),
+                    });
+                }
+                Combo::Steps(steps.clone())
+            }
+            ComboData::Rotation(rotation) => {
+                let unknown = || ScenarioError::UnknownRotation {
+                    champion: champion.to_string(),
+                    rotation: rotation.clone(),
+                };
+                Combo::Rotation(champion_rotation(champion, rotation).ok_or_else(unknown)?)
+            }
+        };
+        return Ok(Scenario {
+            attacker: self.attacker.clone(),
+            build: Vec::new(),
+            target: self.target.clone(),
+            combo,
+        });
+    }
+}
+
+fn validate_combatant(combatant: &Combatant) -> Result<(), ScenarioError> {
+    let Combatant::Champion { name, items, .. } = combatant else {
+        return Ok(());
+    };
+    if load_champion_catalog().get(name).is_none() {
+        return Err(ScenarioError::UnknownChampion(name.clone()));
+    }
+    if let Some(item) = items
+        .iter()
+        .find(|item| !open_wiki_item_json().contains_key(item.as_str()))
+    {
+        return Err(ScenarioError::UnknownItem(item.clone()));
+    }
+    return Ok(());
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ScenarioReport {
+    pub name: String,
+    pub metrics: Vec<(Metric, f64)>,
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        for (metric, value) in &self.metrics {
+            write!(f, "\t{:?} {:.2}", metric, value)?;
+        }
+        return Ok(());
+    }
+}
+
+/**
+ * Runs the scenarios in parallel, one report per scenario in the same order
+ */
+pub fn run_scenarios(files: &[ScenarioFile]) -> Result<Vec<ScenarioReport>, ScenarioError> {
+    let scenarios = files
+        .iter()
+        .map(ScenarioFile::to_scenario)
+        .collect::<Result<Vec<Scenario>, ScenarioError>>()?;
+    let reports = files
+        .iter()
+        .zip(evaluate_batch(&scenarios))
+        .map(|(file, result)| ScenarioReport {
+            name: file.name.clone(),
+            metrics: file
+                .metrics
+                .iter()
+                .map(|metric| (*metric, metric.value(&result)))
+                .collect(),
+        })
+        .collect();
+    return Ok(reports);
+}
+
+pub fn run_scenario_files<P: AsRef<Path>>(
+    paths: &[P],
+) -> Result<Vec<ScenarioReport>, ScenarioError> {
+    let files = paths
+        .iter()
+        .map(|path| ScenarioFile::load(path.as_ref()))
+        .collect::<Result<Vec<ScenarioFile>, ScenarioError>>()?;
+    return run_scenarios(&files);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        champions::{
+            leblanc::Leblanc,
+            skill_order::{Skill, SkillOrder},
+            Vi,
+        },
+        runes::Rune,
+    };
+    use rstest::rstest;
+
+    const VI_ULT_COMBO: &str = "scenarios/vi_ult_combo.json";
+
+    fn parse(json: &str) -> ScenarioFile {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[rstest]
+    fn test_file_matches_scenario_in_code() {
+        let in_code = Scenario {
+            attacker: Combatant::Champion {
+                name: Vi::NAME.to_string(),
+                level: 6,
+                skill_order: Some(SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W])),
+                items: vec!["Duskblade of Draktharr".to_string()],
+                runes: vec![Rune::Electrocute, Rune::SuddenImpact],
+            },
+            build: Vec::new(),
+            target: Combatant::champion(Leblanc::NAME, 6),
+            combo: Combo::Rotation(Vi::ult_combo),
+        };
+        let reports = run_scenario_files(&[VI_ULT_COMBO]).unwrap();
+        let expected = in_code.run();
+        assert_eq!(
+            vec![
+                (Metric::Damage, expected.damage),
+                (Metric::DamageShare, expected.damage_share())
+            ],
+            reports[0].metrics
+        );
+    }
+
+    #[rstest]
+    fn test_every_repo_scenario_runs() {
+        let mut paths: Vec<_> = fs::read_dir("scenarios")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        paths.sort();
+        let reports = run_scenario_files(&paths).unwrap();
+        assert_eq!(paths.len(), reports.len());
+        reports.iter().for_each(|report| {
+            assert!(report
+                .metrics
+                .iter()
+                .all(|(metric, value)| *metric == Metric::Duration || *value > 0.0));
+        });
+    }
+
+    #[rstest]
+    fn test_steps_against_dummy() {
+        let file = parse(
+            r#"{
+                "name": "two autos",
+                "attacker": {"champion": {"name": "Vi", "level": 1}},
+                "target": {"dummy": {"armor": 0, "magic_resist": 0}},
+                "combo": [{"ability": "AUTO"}, {"ability": {"Summoner": "Ignite"}}],
+                "metrics": ["remaining_health"]
+            }"#,
+        );
+        let reports = run_scenarios(&[file]).unwrap();
+        let (metric, value) = reports[0].metrics[0];
+        assert_eq!(Metric::RemainingHealth, metric);
+        assert!(value < 1000.0);
+    }
+
+    #[rstest]
+    #[case(
+        r#"{"name": "x", "attacker": {"champion": {"name": "Vy", "level": 1}}, "target": {"dummy": {"armor": 0, "magic_resist": 0}}, "combo": []}"#,
+        ScenarioError::UnknownChampion("Vy".to_string())
+    )]
+    #[case(
+        r#"{"name": "x", "attacker": {"champion": {"name": "Vi", "level": 1, "items": ["Long Swrod"]}}, "target": {"dummy": {"armor": 0, "magic_resist": 0}}, "combo": []}"#,
+        ScenarioError::UnknownItem("Long Swrod".to_string())
+    )]
+    #[case(
+        r#"{"name": "x", "attacker": {"champion": {"name": "Leblanc", "level": 1}}, "target": {"dummy": {"armor": 0, "magic_resist": 0}}, "combo": "ult_combo"}"#,
+        ScenarioError::UnknownRotation { champion: "Leblanc".to_string(), rotation: "ult_combo".to_string() }
+    )]
+    #[case(
+        r#"{"name": "x", "attacker": {"champion": {"name": "Leblanc", "level": 1}}, "target": {"dummy": {"armor": 0, "magic_resist": 0}}, "combo": [{"ability": "Q"}]}"#,
+        ScenarioError::UnknownAbility { champion: "Leblanc".to_string(), ability: AbilityName::Q }
+    )]
+    #[case(
+        r#"{"name": "x", "attacker": {"champion": {"name": "Vi", "level": 1}}, "target": {"dummy": {"armor": 0, "magic_resist": 0}}, "combo": [{"ability": "W"}]}"#,
+        ScenarioError::UnknownAbility { champion: "Vi".to_string(), ability: AbilityName::W }
+    )]
+    fn test_invalid_names(#[case] json: &str, #[case] expected: ScenarioError) {
+        assert_eq!(Err(expected), parse(json).to_scenario().map(|_| ()));
+    }
+
+    #[rstest]
+    fn test_missing_file() {
+        let result = run_scenario_files(&["scenarios/missing.json"]);
+        assert!(matches!(result, Err(ScenarioError::Read { .. })));
+    }
+}
@@ -0,0 +1,337 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+    armor_reducer::ArmorReducer,
+    attack::{AttackSpeed, BasicAttack},
+    champions::champion::Champion,
+    core::{lethality_to_pen, stat_at_level},
+    load_champion::ChampionStats,
+    target::{StatBuff, VitalityData},
+};
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Stat {
+    pub base: f64,
+    pub bonus: f64,
+}
+
+impl Stat {
+    pub fn total(&self) -> f64 {
+        return self.base + self.bonus;
+    }
+}
+
+/**
+ * Final stats of a champion at a level, split into base (champion growth) and bonus (items, runes, buffs)
+ */
+#[derive(Default, Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct StatSheet {
+    pub level: u8,
+    pub health: Stat,
+    pub mana: Stat,
+    pub attack_damage: Stat,
+    pub ability_power: Stat,
+    pub armor: Stat,
+    pub magic_resist: Stat,
+    pub attack_speed: Stat, // bonus is a percent of attack_speed_ratio
+    pub attack_speed_ratio: f64,
+    pub attack_speed_cap: Option<f64>,
+    pub attack_damage_multiplier: f64, // share of attack damage dealt by basic attacks
+    pub move_speed: Stat,
+    pub health_regen: Stat,
+    pub mana_regen: Stat,
+    pub attack_range: f64,
+    pub ability_haste: f64,
+    pub critical_strike_chance: f64,
+    pub lethality: f64,
+    pub percent_armor_pen: f64,
+    pub flat_magic_pen: f64,
+    pub percent_magic_pen: f64,
+    pub life_steal: f64,
+    pub omnivamp: f64,
+    pub tenacity: f64,
+    pub monster_damage: f64,
+}
+
+fn leveled(base: f64, growth: f64, current: f64, level: u8, buff: f64) -> Stat {
+    return Stat {
+        base: stat_at_level(base, growth, level),
+        bonus: current - base + buff,
+    };
+}
+
+impl StatSheet {
+    /**
+     * `base` is the champion as loaded, `current` the same stats after items and runes were added.
+     * Attack damage and attack speed keep their bonus in separate fields so their base is read from `current`
+     */
+    pub fn new(
+        base: &ChampionStats,
+        current: &ChampionStats,
+        level: u8,
+        buffs: &StatBuff,
+    ) -> StatSheet {
+        let mut health = leveled(
+            base.health,
+            current.health_per_level,
+            current.health,
+            level,
+            0.0,
+        );
+        health.bonus += health.total() * current.percent_health / 100.0;
+        let base_move_speed = base.move_speed;
+        let total_move_speed = (current.move_speed + buffs.move_speed)
+            * (1.0 + (current.percent_movement_speed + buffs.percent_move_speed) / 100.0);
+        return StatSheet {
+            level,
+            health,
+            mana: leveled(base.mana, current.mana_per_level, current.mana, level, 0.0),
+            attack_damage: Stat {
+                base: stat_at_level(
+                    current.base_attack_damage,
+                    current.attack_damage_per_level,
+                    level,
+                ),
+                bonus: current.bonus_attack_damage + buffs.attack_damage,
+            },
+            ability_power: Stat {
+                base: 0.0,
+                bonus: current.ability_power + buffs.ability_power,
+            },
+            armor: leveled(
+                base.armor,
+                current.armor_per_level,
+                current.armor,
+                level,
+                buffs.armor,
+            ),
+            magic_resist: leveled(
+                base.magic_resist,
+                current.magic_resist_per_level,
+                current.magic_resist,
+                level,
+                buffs.magic_resist,
+            ),
+            attack_speed: Stat {
+                base: current.attack_speed,
+                bonus: stat_at_level(0.0, current.attack_speed_per_level, level)
+                    + current.bonus_attack_speed
+                    + buffs.attack_speed,
+            },
+            move_speed: Stat {
+                base: base_move_speed,
+                bonus: total_move_speed - base_move_speed,
+            },
+            health_regen: leveled(
+                base.health_regen,
+                current.health_regen_per_level,
+                current.health_regen,
+                level,
+                0.0,
+            ),
+            mana_regen: leveled(
+                base.mana_regen,
+                current.mana_regen_per_level,
+                current.mana_regen,
+                level,
+                0.0,
+            ),
+            attack_speed_ratio: current.attack_speed_ratio.unwrap_or(current.attack_speed),
+            attack_speed_cap: Some(AttackSpeed::CAP),
+            attack_damage_multiplier: current.attack_damage_multiplier.unwrap_or(1.0),
+            attack_range: current.attack_range,
+            ability_haste: current.ability_haste,
+            critical_strike_chance: current.critical_strike_chance,
+            lethality: current.lethality,
+            percent_armor_pen: current.percent_armor_pen,
+            flat_magic_pen: current.flat_magic_pen,
+            percent_magic_pen: current.percent_magic_pen,
+            life_steal: current.life_steal,
+            omnivamp: current.omnivamp,
+            tenacity: current.tenacity,
+            monster_damage: current.monster_damage,
+        };
+    }
+}
+
+/**
+ * Stat sheets of both sides of a hit, built once while the ability resolves
+ */
+#[derive(Clone, Copy, Debug)]
+pub struct HitSheets {
+    pub attacker: StatSheet,
+    pub target: StatSheet,
+}
+
+impl HitSheets {
+    pub fn new(attacker: &Champion, target: &Champion) -> HitSheets {
+        return HitSheets {
+            attacker: attacker.stat_sheet(),
+            target: target.stat_sheet(),
+        };
+    }
+}
+
+/**
+ * Sheet of stats that were never split, everything not growing with level counts as base
+ */
+impl From<(&ChampionStats, u8)> for StatSheet {
+    fn from(tuple: (&ChampionStats, u8)) -> StatSheet {
+        let (stats, level) = tuple;
+        return StatSheet::new(stats, stats, level, &StatBuff::default());
+    }
+}
+
+impl From<&StatSheet> for BasicAttack {
+    fn from(sheet: &StatSheet) -> BasicAttack {
+        return BasicAttack {
+            damage_multiplier: sheet.attack_damage_multiplier,
+            ..BasicAttack::new(sheet.attack_damage.base, sheet.attack_damage.bonus)
+        };
+    }
+}
+
+impl From<&StatSheet> for AttackSpeed {
+    fn from(sheet: &StatSheet) -> AttackSpeed {
+        return AttackSpeed {
+            base: sheet.attack_speed.base,
+            bonus: sheet.attack_speed.bonus,
+            ratio: sheet.attack_speed_ratio,
+            cap: sheet.attack_speed_cap,
+        };
+    }
+}
+
+impl From<&StatSheet> for VitalityData {
+    fn from(sheet: &StatSheet) -> VitalityData {
+        return VitalityData {
+            base_armor: sheet.armor.base,
+            bonus_armor: sheet.armor.bonus,
+            magic_resist: sheet.magic_resist.total(),
+            max_health: sheet.health.total(),
+            current_health: sheet.health.total(),
+        };
+    }
+}
+
+impl From<&StatSheet> for ArmorReducer {
+    fn from(sheet: &StatSheet) -> ArmorReducer {
+        return ArmorReducer {
+            flat_armor_pen: lethality_to_pen(sheet.lethality, sheet.level),
+            percent_armor_pen: sheet.percent_armor_pen,
+            ..Default::default()
+        };
+    }
+}
+
+impl fmt::Display for StatSheet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "level {}", self.level)?;
+        let split_stats = [
+            ("health", self.health),
+            ("mana", self.mana),
+            ("attack damage", self.attack_damage),
+            ("ability power", self.ability_power),
+            ("armor", self.armor),
+            ("magic resist", self.magic_resist),
+            ("move speed", self.move_speed),
+            ("health regen", self.health_regen),
+            ("mana regen", self.mana_regen),
+        ];
+        for (name, stat) in split_stats {
+            writeln!(
+                f,
+                "{:<16}{:>9.2} ({:.2} + {:.2})",
+                name,
+                stat.total(),
+                stat.base,
+                stat.bonus
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<16}{:>9.3} ({:.3} + {:.2}%)",
+            "attack speed",
+            AttackSpeed::from(self).get_attacks_per_second(),
+            self.attack_speed.base,
+            self.attack_speed.bonus
+        )?;
+        let flat_stats = [
+            ("attack range", self.attack_range),
+            ("ability haste", self.ability_haste),
+            ("crit chance", self.critical_strike_chance),
+            ("lethality", self.lethality),
+            ("armor pen %", self.percent_armor_pen),
+            ("magic pen", self.flat_magic_pen),
+            ("magic pen %", self.percent_magic_pen),
+            ("life steal", self.life_steal),
+            ("omnivamp", self.omnivamp),
+            ("tenacity", self.tenacity),
+            ("monster damage", self.monster_damage),
+        ];
+        for (name, value) in flat_stats {
+            writeln!(f, "{:<16}{:>9.2}", name, value)?;
+        }
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        champions::{champion::Champion, Vi},
+        load_champion::load_champion_stats,
+        load_wiki_item::apply_item_to_champ,
+    };
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    fn vi_at(level: u8, item_names: &[&str]) -> Champion {
+        let mut vi_data = Vi::new();
+        let mut vi = Champion::new(
+            Vi::NAME.to_string(),
+            level,
+            [0, 0, 0, 0],
+            vi_data.get_name_closures(),
+        );
+        item_names
+            .iter()
+            .for_each(|item_name| apply_item_to_champ(item_name, &mut vi));
+        return vi;
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(11)]
+    #[case(18)]
+    fn test_items_are_bonus_at_every_level(#[case] level: u8) {
+        let plain = vi_at(level, &[]).stat_sheet();
+        let sheet = vi_at(level, &["Chain Vest", "Ruby Crystal", "Long Sword"]).stat_sheet();
+        assert_relative_eq!(plain.armor.base, sheet.armor.base);
+        assert_relative_eq!(40.0, sheet.armor.bonus);
+        assert_relative_eq!(150.0, sheet.health.bonus);
+        assert_relative_eq!(10.0, sheet.attack_damage.bonus);
+        assert_relative_eq!(0.0, plain.armor.bonus);
+    }
+
+    #[rstest]
+    fn test_base_stats_grow_with_level() {
+        let stats = load_champion_stats(Vi::NAME.to_string());
+        let sheet = vi_at(18, &[]).stat_sheet();
+        assert_relative_eq!(stats.armor + 17.0 * stats.armor_per_level, sheet.armor.base);
+        assert_relative_eq!(
+            stats.health + 17.0 * stats.health_per_level,
+            sheet.health.base
+        );
+    }
+
+    #[rstest]
+    fn test_sheet_is_printable_and_serializable() {
+        let sheet = vi_at(6, &["Chain Vest"]).stat_sheet();
+        assert!(format!("{}", sheet).contains("armor"));
+        let value = serde_json::to_value(sheet).unwrap();
+        assert_relative_eq!(40.0, value["armor"]["bonus"].as_f64().unwrap());
+    }
+}
@@ -0,0 +1,293 @@
+use serde::Deserialize;
+
+use crate::{
+    champions::champion::{AbilityFn, AbilityName, CastingData, Champion},
+    core::linear_by_level,
+    target::{
+        CrowdControl, DamageOverTime, DamageType, DotPolicy, EffectData, EffectResult, Target,
+    },
+    time_manager::TIME,
+    world::{EntityId, World},
+};
+
+const FLASH_DISTANCE: f64 = 400.0;
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, Deserialize)]
+pub enum SummonerSpell {
+    Ignite,
+    Exhaust,
+    Smite,
+    ChillingSmite,
+    ChallengingSmite,
+    Flash,
+}
+
+impl SummonerSpell {
+    pub const ALL: [SummonerSpell; 6] = [
+        SummonerSpell::Ignite,
+        SummonerSpell::Exhaust,
+        SummonerSpell::Smite,
+        SummonerSpell::ChillingSmite,
+        SummonerSpell::ChallengingSmite,
+        SummonerSpell::Flash,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        return match self {
+            SummonerSpell::Ignite => "Ignite",
+            SummonerSpell::Exhaust => "Exhaust",
+            SummonerSpell::Smite => "Smite",
+            SummonerSpell::ChillingSmite => "Chilling Smite",
+            SummonerSpell::ChallengingSmite => "Challenging Smite",
+            SummonerSpell::Flash => "Flash",
+        };
+    }
+
+    pub fn cooldown(&self) -> f64 {
+        return match self {
+            SummonerSpell::Ignite => 180.0,
+            SummonerSpell::Exhaust => 210.0,
+            SummonerSpell::Smite
+            | SummonerSpell::ChillingSmite
+            | SummonerSpell::ChallengingSmite => 90.0,
+            SummonerSpell::Flash => 300.0,
+        };
+    }
+
+    /**
+     * True damage dealt to minions and monsters, upgraded smites hit harder
+     */
+    pub fn smite_damage(&self) -> Option<f64> {
+        return match self {
+            SummonerSpell::Smite => Some(600.0),
+            SummonerSpell::ChillingSmite | SummonerSpell::ChallengingSmite => Some(900.0),
+            _ => None,
+        };
+    }
+
+    /**
+     * Cast range on the target, flash blinks towards it from anywhere
+     */
+    pub fn range(&self) -> Option<f64> {
+        return match self {
+            SummonerSpell::Ignite => Some(600.0),
+            SummonerSpell::Exhaust => Some(650.0),
+            SummonerSpell::Smite
+            | SummonerSpell::ChillingSmite
+            | SummonerSpell::ChallengingSmite => Some(500.0),
+            SummonerSpell::Flash => None,
+        };
+    }
+}
+
+fn apply_to_target(
+    target: &mut Champion,
+    name: &str,
+    duration: f64,
+    result: EffectResult,
+) -> Option<()> {
+    target.upsert_effect(EffectData {
+        unique_name: name.to_string(),
+        expiry: TIME.with(|time| *time.borrow() + duration),
+        result,
+    })
+}
+
+/**
+ * Casting a summoner spell still on cooldown does nothing
+ */
+fn summoner_spell(spell: SummonerSpell) -> impl Fn(&mut World, EntityId, EntityId, &CastingData) {
+    return move |world: &mut World,
+                 attacker: EntityId,
+                 target_id: EntityId,
+                 _casting_data: &CastingData| {
+        let is_unit = world[target_id].unit_kind().is_minion_or_monster();
+        // only upgraded smites can be cast on champions
+        if world[attacker].is_on_cooldown(spell.name())
+            || (spell == SummonerSpell::Smite && !is_unit)
+        {
+            return;
+        }
+        world[attacker].put_on_cooldown(spell.name(), spell.cooldown());
+        if let Some(damage) = spell.smite_damage().filter(|_| is_unit) {
+            world.deal_typed_damage(attacker, target_id, damage, DamageType::True);
+            return;
+        }
+        let level = world[attacker].level;
+        let destination = world[target_id].position;
+        let target = &mut world[target_id];
+        match spell {
+            SummonerSpell::Ignite => {
+                let damage = 50.0 + 20.0 * level as f64;
+                DamageOverTime::new(
+                    attacker,
+                    damage,
+                    DamageType::True,
+                    5.0,
+                    1.0,
+                    DotPolicy::Refresh,
+                )
+                .upsert_to_champ(target, spell.name());
+                apply_to_target(
+                    target,
+                    spell.name(),
+                    5.0,
+                    EffectResult::GrievousWounds(40.0),
+                );
+            }
+            SummonerSpell::Exhaust => {
+                apply_to_target(
+                    target,
+                    spell.name(),
+                    3.0,
+                    EffectResult::OutgoingDamageAmplifier(-40.0),
+                );
+                target.apply_crowd_control("Exhaust Slow", CrowdControl::Slow(30.0), 3.0);
+            }
+            SummonerSpell::Smite => {}
+            SummonerSpell::ChillingSmite => {
+                let damage = 20.0 + 8.0 * level as f64;
+                world.deal_typed_damage(attacker, target_id, damage, DamageType::True);
+            }
+            SummonerSpell::ChallengingSmite => {
+                let damage = linear_by_level(48.0, 128.0, level);
+                DamageOverTime::new(
+                    attacker,
+                    damage,
+                    DamageType::True,
+                    3.0,
+                    1.0,
+                    DotPolicy::Refresh,
+                )
+                .upsert_to_champ(target, spell.name());
+                apply_to_target(
+                    target,
+                    spell.name(),
+                    4.0,
+                    EffectResult::OutgoingDamageAmplifier(-20.0),
+                );
+            }
+            SummonerSpell::Flash => world[attacker].dash_towards(&destination, FLASH_DISTANCE),
+        }
+    };
+}
+
+pub fn summoner_abilities() -> Vec<(AbilityName, Box<AbilityFn>)> {
+    return SummonerSpell::ALL
+        .into_iter()
+        .map(|spell| {
+            let func: Box<AbilityFn> = Box::new(summoner_spell(spell));
+            (AbilityName::Summoner(spell), func)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        runes::{apply_runes_to_champ, Rune},
+        units::MonsterKind,
+    };
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    fn cast(world: &mut World, attacker: EntityId, name: AbilityName, target: EntityId) {
+        world.execute_ability(attacker, &name, target, &CastingData::default());
+    }
+
+    fn dummies() -> (World, EntityId, EntityId) {
+        let mut world = World::new();
+        let attacker = world.spawn(Champion::new_dummy());
+        let target = world.spawn(Champion::new_dummy());
+        (world, attacker, target)
+    }
+
+    #[rstest]
+    #[case(SummonerSpell::Ignite, 0.0, 70.0)]
+    #[case(SummonerSpell::ChillingSmite, 28.0, 28.0)]
+    #[case(SummonerSpell::ChallengingSmite, 0.0, 48.0)]
+    #[case(SummonerSpell::Exhaust, 0.0, 0.0)]
+    #[case(SummonerSpell::Flash, 0.0, 0.0)]
+    #[case(SummonerSpell::Smite, 0.0, 0.0)]
+    fn test_summoner_damage_and_cooldown(
+        #[case] spell: SummonerSpell,
+        #[case] expected_on_cast: f64,
+        #[case] expected_total: f64,
+    ) {
+        let mut world = World::new();
+        let attacker = world.spawn(Champion::new_dummy_with_resist(100.0, 100.0));
+        let target = world.spawn(Champion::new_dummy_with_resist(100.0, 100.0));
+        cast(&mut world, attacker, AbilityName::Summoner(spell), target);
+        assert_relative_eq!(expected_on_cast, world[target].get_missing_health());
+        world.advance_time(10.0);
+        assert_relative_eq!(expected_total, world[target].get_missing_health());
+        cast(&mut world, attacker, AbilityName::Summoner(spell), target);
+        world.advance_time(10.0);
+        assert_relative_eq!(expected_total, world[target].get_missing_health());
+    }
+
+    #[rstest]
+    fn test_ignite_ticks() {
+        let (mut world, attacker, target) = dummies();
+        let ignite = AbilityName::Summoner(SummonerSpell::Ignite);
+        cast(&mut world, attacker, ignite, target);
+        world.advance_time(2.5);
+        assert_relative_eq!(28.0, world[target].get_missing_health());
+        world.advance_time(2.5);
+        assert_relative_eq!(70.0, world[target].get_missing_health());
+    }
+
+    #[rstest]
+    fn test_ignite_grievous_wounds() {
+        let (mut world, attacker, target) = dummies();
+        let ignite = AbilityName::Summoner(SummonerSpell::Ignite);
+        cast(&mut world, attacker, ignite, target);
+        world.advance_time(5.0);
+        world[target].heal(50.0);
+        assert_relative_eq!(40.0, world[target].get_missing_health());
+    }
+
+    #[rstest]
+    fn test_exhaust_reduces_damage_dealt() {
+        let (mut world, exhausted, target) = dummies();
+        let caster = world.spawn(Champion::new_dummy());
+        world[exhausted].stats.base_attack_damage = 100.0;
+        let exhaust = AbilityName::Summoner(SummonerSpell::Exhaust);
+        cast(&mut world, caster, exhaust, exhausted);
+        cast(&mut world, exhausted, AbilityName::AUTO, target);
+        assert_relative_eq!(60.0, world[target].get_missing_health());
+    }
+
+    #[rstest]
+    fn test_exhaust_slows() {
+        let (mut world, attacker, target) = dummies();
+        world[target].stats.move_speed = 300.0;
+        let exhaust = AbilityName::Summoner(SummonerSpell::Exhaust);
+        cast(&mut world, attacker, exhaust, target);
+        assert_relative_eq!(210.0, world[target].get_move_speed());
+        world.advance_time(3.5);
+        assert_relative_eq!(300.0, world[target].get_move_speed());
+    }
+
+    #[rstest]
+    fn test_flash_triggers_sudden_impact() {
+        let (mut world, attacker, target) = dummies();
+        apply_runes_to_champ(&[Rune::SuddenImpact], &mut world[attacker]);
+        let flash = AbilityName::Summoner(SummonerSpell::Flash);
+        cast(&mut world, attacker, flash, target);
+        assert!(world[attacker].has_effect("Sudden Impact"));
+    }
+
+    #[rstest]
+    #[case(SummonerSpell::Smite, 600.0)]
+    #[case(SummonerSpell::ChillingSmite, 900.0)]
+    #[case(SummonerSpell::Ignite, 0.0)]
+    fn test_smite_monsters(#[case] spell: SummonerSpell, #[case] expected: f64) {
+        let mut world = World::new();
+        let attacker = world.spawn(Champion::new_dummy());
+        let target = world.spawn(Champion::new_monster(MonsterKind::BlueSentinel, 1));
+        cast(&mut world, attacker, AbilityName::Summoner(spell), target);
+        assert_relative_eq!(expected, world[target].get_missing_health());
+    }
+}
@@ -0,0 +1,322 @@
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::{
+    champions::{
+        champion::{AbilityName, CastingData, Champion},
+        champion_closures,
+        skill_order::SkillOrder,
+    },
+    jungle::Rotation,
+    load_wiki_item::apply_items_to_champ,
+    runes::{apply_runes_to_champ, Rune},
+    time_manager::{reset_time, TIME},
+    world::World,
+};
+
+/**
+ * Recipe for a combatant, built from scratch by whichever thread runs the scenario
+ */
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Combatant {
+    Champion {
+        name: String,
+        level: u8,
+        #[serde(default)]
+        skill_order: Option<SkillOrder>, // none leaves every ability unlearned
+        #[serde(default)]
+        items: Vec<String>,
+        #[serde(default)]
+        runes: Vec<Rune>,
+    },
+    Dummy {
+        armor: f64,
+        magic_resist: f64,
+    },
+}
+
+impl Combatant {
+    /**
+     * Champion without items, runes or skill points, left with its autos and summoners
+     */
+    pub fn champion(name: &str, level: u8) -> Combatant {
+        return Combatant::Champion {
+            name: name.to_string(),
+            level,
+            skill_order: None,
+            items: Vec::new(),
+            runes: Vec::new(),
+        };
+    }
+
+    pub fn build(&self) -> Champion {
+        return self.build_with_items(&[]);
+    }
+
+    /**
+     * Dummies ignore the extra items, champions get them on top of their own as a single full build
+     */
+    pub fn build_with_items(&self, extra_items: &[String]) -> Champion {
+        match self {
+            Combatant::Champion {
+                name,
+                level,
+                skill_order,
+                items,
+                runes,
+            } => {
+                let abilities = champion_closures(name);
+                let mut champion = match skill_order {
+                    Some(order) => Champion::new_with_skill_order(
+                        name.clone(),
+                        *level,
+                        order.clone(),
+                        abilities,
+                    ),
+                    None => Champion::new(name.clone(), *level, [0, 0, 0, 0], abilities),
+                };
+                let item_names: Vec<&str> = items
+                    .iter()
+                    .chain(extra_items)
+                    .map(|item_name| item_name.as_str())
+                    .collect();
+                apply_items_to_champ(&item_names, &mut champion);
+                apply_runes_to_champ(runes, &mut champion);
+                return champion;
+            }
+            Combatant::Dummy {
+                armor,
+                magic_resist,
+            } => Champion::new_dummy_with_resist(*armor, *magic_resist),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ComboStep {
+    pub ability: AbilityName,
+    #[serde(default)]
+    pub charge: f64,
+}
+
+/**
+ * Abilities the attacker casts, at the ranks it has learned
+ */
+#[derive(Clone, Debug)]
+pub enum Combo {
+    Rotation(Rotation),
+    Steps(Vec<ComboStep>),
+}
+
+impl Combo {
+    pub fn casts(&self) -> Vec<(AbilityName, CastingData)> {
+        return match self {
+            Combo::Rotation(rotation) => rotation(),
+            Combo::Steps(steps) => steps
+                .iter()
+                .map(|step| {
+                    let data = CastingData {
+                        charge: step.charge,
+                        ..Default::default()
+                    };
+                    (step.ability.clone(), data)
+                })
+                .collect(),
+        };
+    }
+}
+
+/**
+ * The attacker, with the build on top of its own items, running the combo on the target
+ */
+#[derive(Clone, Debug)]
+pub struct Scenario {
+    pub attacker: Combatant,
+    pub build: Vec<String>,
+    pub target: Combatant,
+    pub combo: Combo,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScenarioResult {
+    pub damage: f64,
+    pub target_max_health: f64,
+    pub duration: f64, // seconds spent walking into range
+}
+
+impl ScenarioResult {
+    pub fn damage_share(&self) -> f64 {
+        return self.damage / self.target_max_health;
+    }
+}
+
+impl Scenario {
+    /**
+     * Spawns both sides in a world of their own, with the clock of the current thread running from 0
+     */
+    pub fn run(&self) -> ScenarioResult {
+        reset_time();
+        let attacker = self.attacker.build_with_items(&self.build);
+        let combo = self.combo.casts();
+
+        let mut world = World::new();
+        let attacker = world.spawn(attacker);
+        let target = world.spawn(self.target.build());
+        world.execute_combo(attacker, combo, target);
+        return ScenarioResult {
+            damage: world[target].get_missing_health(),
+            target_max_health: world[target].get_max_health(),
+            duration: TIME.with(|time| *time.borrow()),
+        };
+    }
+}
+
+/**
+ * Runs every scenario on all cores, results in the order of the scenarios
+ */
+pub fn evaluate_batch(scenarios: &[Scenario]) -> Vec<ScenarioResult> {
+    let threads = thread::available_parallelism().map_or(1, |count| count.get());
+    return evaluate_batch_on_threads(scenarios, threads, Scenario::run);
+}
+
+/**
+ * Splits the scenarios into one contiguous chunk per thread. Each scenario starts on a reset clock and
+ * builds its own combatants, so the results do not depend on the number of threads
+ */
+pub fn evaluate_batch_on_threads<T: Send>(
+    scenarios: &[Scenario],
+    threads: usize,
+    evaluate: impl Fn(&Scenario) -> T + Sync,
+) -> Vec<T> {
+    if scenarios.is_empty() {
+        return Vec::new();
+    }
+    let chunk_size = scenarios.len().div_ceil(threads.max(1));
+    let evaluate = &evaluate;
+    return thread::scope(|scope| {
+        let handles: Vec<_> = scenarios
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|scenario| {
+                            reset_time();
+                            evaluate(scenario)
+                        })
+                        .collect::<Vec<T>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("scenario panicked"))
+            .collect()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::champions::{
+        leblanc::Leblanc,
+        skill_order::{Skill, SkillOrder},
+        Vi,
+    };
+    use rstest::rstest;
+
+    fn vi_scenario(build: &[&str]) -> Scenario {
+        return Scenario {
+            attacker: Combatant::Champion {
+                name: Vi::NAME.to_string(),
+                level: 6,
+                skill_order: Some(SkillOrder::max_by_priority([Skill::E, Skill::Q, Skill::W])),
+                items: Vec::new(),
+                runes: vec![Rune::Electrocute],
+            },
+            build: build
+                .iter()
+                .map(|item_name| item_name.to_string())
+                .collect(),
+            target: Combatant::champion(Leblanc::NAME, 6),
+            combo: Combo::Rotation(Vi::ult_combo),
+        };
+    }
+
+    fn builds() -> Vec<Scenario> {
+        let builds: [&[&str]; 5] = [
+            &[],
+            &["Long Sword"],
+            &["Duskblade of Draktharr"],
+            &["Serrated Dirk", "Last Whisper"],
+            &["Black Cleaver", "Sheen"],
+        ];
+        return builds.iter().map(|build| vi_scenario(build)).collect();
+    }
+
+    #[rstest]
+    fn test_batch_matches_serial_runs() {
+        let scenarios = builds();
+        let serial: Vec<ScenarioResult> = scenarios.iter().map(Scenario::run).collect();
+        assert_eq!(serial, evaluate_batch(&scenarios));
+        assert!(serial[2].damage > serial[0].damage);
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(16)]
+    fn test_batch_independent_of_thread_count(#[case] threads: usize) {
+        let scenarios = builds();
+        assert_eq!(
+            evaluate_batch_on_threads(&scenarios, 1, Scenario::run),
+            evaluate_batch_on_threads(&scenarios, threads, Scenario::run)
+        );
+    }
+
+    #[rstest]
+    fn test_dummy_target() {
+        let scenario = Scenario {
+            target: Combatant::Dummy {
+                armor: 0.0,
+                magic_resist: 0.0,
+            },
+            ..vi_scenario(&[])
+        };
+        let result = evaluate_batch(&[scenario]);
+        assert_eq!(1000.0, result[0].target_max_health);
+        assert!(result[0].damage_share() > 0.0);
+    }
+
+    #[rstest]
+    fn test_no_skill_order_leaves_abilities_unlearned() {
+        let cast = |ability: AbilityName| {
+            let scenario = Scenario {
+                attacker: Combatant::champion(Vi::NAME, 6),
+                build: Vec::new(),
+                target: Combatant::champion(Leblanc::NAME, 6),
+                combo: Combo::Steps(vec![ComboStep {
+                    ability,
+                    charge: 0.0,
+                }]),
+            };
+            scenario.run().damage
+        };
+        assert_eq!(0.0, cast(AbilityName::R));
+        assert!(cast(AbilityName::AUTO) > 0.0);
+    }
+
+    #[rstest]
+    fn test_combo_steps() {
+        let steps: Vec<ComboStep> = serde_json::from_str(
+            r#"[{"ability": "E"}, {"ability": "Q", "charge": 1.25}, {"ability": "AUTO"}]"#,
+        )
+        .unwrap();
+        let combo = Combo::Steps(steps).casts();
+        assert_eq!(AbilityName::E, combo[0].0);
+        assert_eq!(1.25, combo[1].1.charge);
+        assert_eq!(AbilityName::AUTO, combo[2].0);
+    }
+}
@@ -1,15 +1,39 @@
-use std::{cell::RefCell, fmt, mem, rc::Weak};
+use std::{fmt, mem};
 
 use crate::{
     armor_reducer::ArmorReducer,
     champions::champion::{AbilityName, CastingData, Champion},
-    core::stat_at_level,
     load_champion::ChampionStats,
+    stat_sheet::StatSheet,
     time_manager::TIME,
+    units::UnitKind,
+    world::EntityId,
 };
 
+/**
+ * Anything abilities can damage: champions, minions, monsters and structures
+ */
 pub trait Target {
     fn get_vitality_data(&self) -> VitalityData;
+    fn unit_kind(&self) -> UnitKind;
+
+    fn is_immune_to(&self, name: &AbilityName) -> bool {
+        self.unit_kind().is_immune_to(name)
+    }
+
+    /**
+     * Percent less damage taken from champions
+     */
+    fn damage_reduction(&self) -> f64 {
+        self.unit_kind().damage_reduction()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageType {
+    Physical,
+    Magic,
+    True,
 }
 
 #[derive(Default, Clone)]
@@ -28,17 +52,242 @@ pub struct EffectData {
     pub result: EffectResult,
 }
 
-#[derive(Debug)]
-pub struct ThreeHitApplyInfo {
+#[derive(Debug, Clone)]
+pub struct StackApplyInfo {
     pub ttl: f64,
     pub unique_name: String,
     pub result: Box<EffectResult>,
 }
 
-#[derive(Debug)]
-pub struct ThreeHit {
-    pub hit_count: u8,
-    pub on_third_hit: ThreeHitApplyInfo,
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StackDecay {
+    #[default]
+    All, // every stack drops once the counter expires
+    One, // stacks drop one at a time, ttl apart
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StackReset {
+    #[default]
+    OnThreshold, // reaching the threshold consumes the stacks, ie denting blows
+    Never, // stacks are kept after proccing, ie conqueror at max stacks
+}
+
+/**
+ * Counter that gains stacks on each application, ie denting blows, black cleaver's carve or conqueror.
+ * per_stack is multiplied by the stack count while active, on_threshold fires once the threshold is reached
+ */
+#[derive(Debug, Clone, Default)]
+pub struct StackCounter {
+    pub count: u8,
+    pub increment: u8,
+    pub max_stacks: u8,
+    pub ttl: f64,
+    pub threshold: Option<u8>,
+    pub on_threshold: Option<StackApplyInfo>,
+    pub per_stack: Option<Box<EffectResult>>,
+    pub decay: StackDecay,
+    pub reset: StackReset,
+}
+
+impl StackCounter {
+    /**
+     * Fires on_threshold on the nth application within ttl of the previous one, then starts over
+     */
+    pub fn on_nth_hit(hits: u8, on_threshold: StackApplyInfo, ttl: f64) -> StackCounter {
+        StackCounter {
+            increment: 1,
+            max_stacks: hits,
+            ttl,
+            threshold: Some(hits),
+            on_threshold: Some(on_threshold),
+            ..Default::default()
+        }
+    }
+
+    pub fn expiry_from(&self, now: f64) -> f64 {
+        match self.decay {
+            StackDecay::All => now + self.ttl,
+            StackDecay::One => now + self.ttl * self.count as f64,
+        }
+    }
+
+    /**
+     * Stacks left at the given time, for a counter expiring at expiry
+     */
+    pub fn count_at(&self, expiry: f64, now: f64) -> u8 {
+        if now > expiry {
+            return 0;
+        }
+        match self.decay {
+            StackDecay::All => self.count,
+            StackDecay::One => self
+                .count
+                .min(((expiry - now) / self.ttl).ceil().max(1.0) as u8),
+        }
+    }
+
+    pub fn upsert_to_champ(mut self, champion: &mut Champion, unique_name: &str) {
+        self.count = self.increment.min(self.max_stacks);
+        let expiry = self.expiry_from(TIME.with(|time| *time.borrow()));
+        champion.upsert_effect(EffectData {
+            unique_name: unique_name.to_string(),
+            expiry,
+            result: EffectResult::StackCounter(self),
+        });
+    }
+
+    /**
+     * The per stack effect multiplied out, only numeric effects scale, the rest apply once while stacked
+     */
+    pub fn stacked_effect(&self, count: u8) -> Option<EffectResult> {
+        let per_stack = self.per_stack.as_deref()?;
+        if count == 0 {
+            return None;
+        }
+        let count = count as f64;
+        return Some(match per_stack {
+            EffectResult::ArmorReducer(reducer) => EffectResult::ArmorReducer(ArmorReducer {
+                flat_armor_reduction: reducer.flat_armor_reduction * count,
+                percent_armor_reduction: reducer.percent_armor_reduction * count,
+                flat_armor_pen: reducer.flat_armor_pen * count,
+                percent_armor_pen: reducer.percent_armor_pen * count,
+                percent_bonus_armor_pen: reducer.percent_bonus_armor_pen * count,
+            }),
+            EffectResult::IncomingDamageAmplifier(percent) => {
+                EffectResult::IncomingDamageAmplifier(percent * count)
+            }
+            EffectResult::OutgoingDamageAmplifier(percent) => {
+                EffectResult::OutgoingDamageAmplifier(percent * count)
+            }
+            EffectResult::Penetration {
+                lethality,
+                flat_magic_pen,
+            } => EffectResult::Penetration {
+                lethality: lethality * count,
+                flat_magic_pen: flat_magic_pen * count,
+            },
+            EffectResult::StatBuff(buff) => EffectResult::StatBuff(buff.scaled(count)),
+            other => other.clone(),
+        });
+    }
+}
+
+/**
+ * Stats granted while the effect lasts, ie conqueror's adaptive force or a sterak's style resist buff
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatBuff {
+    pub attack_damage: f64, // bonus
+    pub ability_power: f64,
+    pub attack_speed: f64, // percent bonus
+    pub armor: f64,
+    pub magic_resist: f64,
+    pub move_speed: f64,
+    pub percent_move_speed: f64,
+}
+
+impl StatBuff {
+    pub fn scaled(&self, factor: f64) -> StatBuff {
+        StatBuff {
+            attack_damage: self.attack_damage * factor,
+            ability_power: self.ability_power * factor,
+            attack_speed: self.attack_speed * factor,
+            armor: self.armor * factor,
+            magic_resist: self.magic_resist * factor,
+            move_speed: self.move_speed * factor,
+            percent_move_speed: self.percent_move_speed * factor,
+        }
+    }
+
+    pub fn add(&self, other: &StatBuff) -> StatBuff {
+        StatBuff {
+            attack_damage: self.attack_damage + other.attack_damage,
+            ability_power: self.ability_power + other.ability_power,
+            attack_speed: self.attack_speed + other.attack_speed,
+            armor: self.armor + other.armor,
+            magic_resist: self.magic_resist + other.magic_resist,
+            move_speed: self.move_speed + other.move_speed,
+            percent_move_speed: self.percent_move_speed + other.percent_move_speed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DotPolicy {
+    Refresh, // reapplying replaces the running instance
+    Stack,   // every application ticks independently
+}
+
+/**
+ * Damage dealt every tick_interval seconds until the ticks run out, ie ignite or liandry's burn
+ */
+#[derive(Clone)]
+pub struct DamageOverTime {
+    pub source: EntityId,
+    pub tick_interval: f64,
+    pub tick_damage: f64,
+    pub damage_type: DamageType,
+    pub policy: DotPolicy,
+    pub next_tick: f64,
+    pub remaining_ticks: u32,
+}
+
+impl fmt::Debug for DamageOverTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "DamageOverTime {:?} {:?} every {:?}s, {:?} ticks left",
+            self.tick_damage, self.damage_type, self.tick_interval, self.remaining_ticks
+        )
+    }
+}
+
+impl DamageOverTime {
+    /**
+     * Splits total_damage evenly over the ticks in duration, the first tick lands one interval after application
+     */
+    pub fn new(
+        source: EntityId,
+        total_damage: f64,
+        damage_type: DamageType,
+        duration: f64,
+        tick_interval: f64,
+        policy: DotPolicy,
+    ) -> DamageOverTime {
+        let tick_count = (duration / tick_interval).round().max(1.0) as u32;
+        DamageOverTime {
+            source,
+            tick_interval,
+            tick_damage: total_damage / tick_count as f64,
+            damage_type,
+            policy,
+            next_tick: TIME.with(|time| *time.borrow() + tick_interval),
+            remaining_ticks: tick_count,
+        }
+    }
+
+    pub fn upsert_to_champ(self, champion: &mut Champion, unique_name: &str) {
+        let expiry = self.next_tick + self.tick_interval * (self.remaining_ticks - 1) as f64;
+        champion.upsert_effect(EffectData {
+            unique_name: unique_name.to_string(),
+            expiry,
+            result: EffectResult::DamageOverTime(self),
+        });
+    }
+
+    /**
+     * Ticks due by the given time, advancing past them
+     */
+    pub fn take_due_ticks(&mut self, now: f64) -> u32 {
+        let mut ticks = 0;
+        while self.remaining_ticks > 0 && self.next_tick <= now {
+            self.remaining_ticks -= 1;
+            self.next_tick += self.tick_interval;
+            ticks += 1;
+        }
+        return ticks;
+    }
 }
 
 impl PartialEq for EffectData {
@@ -48,22 +297,36 @@ impl PartialEq for EffectData {
     }
 }
 
-impl ThreeHit {
-    pub fn upsert_to_champ(champion: &mut Champion, resulting_effect: ThreeHitApplyInfo, ttl: f64) {
-        let three_hit_name = resulting_effect.unique_name.clone();
-        let three_hit_effect = ThreeHit {
-            hit_count: 0,
-            on_third_hit: resulting_effect,
-        };
-        let three_hit_data = EffectData {
-            unique_name: three_hit_name,
-            expiry: TIME.with(|time| *time.borrow() + ttl),
-            result: EffectResult::ThreeHit(three_hit_effect),
-        };
-        champion.upsert_effect(three_hit_data);
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CrowdControl {
+    Stun,
+    Knockup,
+    Suppression,
+    Root,
+    Slow(f64), // percent move speed
+}
+
+impl CrowdControl {
+    pub fn prevents_acting(&self) -> bool {
+        matches!(
+            self,
+            CrowdControl::Stun | CrowdControl::Knockup | CrowdControl::Suppression
+        )
+    }
+
+    pub fn prevents_moving(&self) -> bool {
+        self.prevents_acting() || matches!(self, CrowdControl::Root)
+    }
+
+    /**
+     * Airborne and suppression durations ignore tenacity
+     */
+    pub fn reduced_by_tenacity(&self) -> bool {
+        !matches!(self, CrowdControl::Knockup | CrowdControl::Suppression)
     }
 }
 
+#[derive(Clone)]
 pub enum EmpowerState {
     Cooldown,
     Active(AbilityEffect, f64),
@@ -71,22 +334,52 @@ pub enum EmpowerState {
 
 #[derive(Clone)]
 pub struct AbilityEffect {
-    pub attacker: Weak<RefCell<Champion>>,
+    pub attacker: EntityId,
     pub name: AbilityName,
     pub data: CastingData,
 }
+#[derive(Clone)]
 pub enum EffectResult {
-    ThreeHit(ThreeHit),
+    StackCounter(StackCounter),
     ArmorReducer(ArmorReducer),
     EmpowerNextAttack(EmpowerState),
     AbilityEffect(AbilityEffect),
+    Cooldown,
+    IncomingDamageAmplifier(f64), // percent, on the target
+    OutgoingDamageAmplifier(f64), // percent, on the attacker
+    DamageReductionAgainst { target: EntityId, percent: f64 }, // on the attacker, only against that target
+    Penetration { lethality: f64, flat_magic_pen: f64 },
+    GrievousWounds(f64), // percent healing reduction
+    DamageOverTime(DamageOverTime),
+    StatBuff(StatBuff),
+    ExecuteThreshold(f64), // percent max health, on the attacker
+    CrowdControl(CrowdControl),
 }
 
 impl fmt::Debug for EffectResult {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::ThreeHit(inside) => write!(f, "{:?}", inside),
+            Self::StackCounter(inside) => write!(f, "{:?}", inside),
             Self::ArmorReducer(inside) => write!(f, "{:?}", inside),
+            Self::Cooldown => write!(f, "Cooldown"),
+            Self::Penetration {
+                lethality,
+                flat_magic_pen,
+            } => write!(f, "Penetration {:?} {:?}", lethality, flat_magic_pen),
+            Self::IncomingDamageAmplifier(percent) => {
+                write!(f, "IncomingDamageAmplifier {:?}", percent)
+            }
+            Self::OutgoingDamageAmplifier(percent) => {
+                write!(f, "OutgoingDamageAmplifier {:?}", percent)
+            }
+            Self::DamageReductionAgainst { target, percent } => {
+                write!(f, "DamageReductionAgainst {:?} {:?}", target, percent)
+            }
+            Self::GrievousWounds(percent) => write!(f, "GrievousWounds {:?}", percent),
+            Self::DamageOverTime(inside) => write!(f, "{:?}", inside),
+            Self::StatBuff(inside) => write!(f, "{:?}", inside),
+            Self::ExecuteThreshold(percent) => write!(f, "ExecuteThreshold {:?}", percent),
+            Self::CrowdControl(inside) => write!(f, "{:?}", inside),
             Self::AbilityEffect(AbilityEffect {
                 attacker: _,
                 name,
@@ -106,18 +399,159 @@ impl fmt::Debug for EffectResult {
 
 impl From<(&ChampionStats, u8)> for VitalityData {
     fn from(tuple: (&ChampionStats, u8)) -> VitalityData {
-        let (stats, level) = tuple;
-        let base_armor = stat_at_level(stats.armor, stats.armor_per_level, level);
-        let magic_resist = stat_at_level(stats.magic_resist, stats.magic_resist_per_level, level);
-        let max_health = stat_at_level(stats.health, stats.health_per_level, level);
-        let target = VitalityData {
-            base_armor,
-            bonus_armor: 0.0,
-            max_health,
-            current_health: max_health,
-            magic_resist,
-        };
-
-        return target;
+        return (&StatSheet::from(tuple)).into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{time_manager::advance_time, world::World};
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    fn carve() -> StackCounter {
+        StackCounter {
+            increment: 1,
+            max_stacks: 6,
+            ttl: 6.0,
+            per_stack: Some(Box::new(EffectResult::ArmorReducer(ArmorReducer {
+                percent_armor_reduction: 5.0,
+                ..Default::default()
+            }))),
+            ..Default::default()
+        }
+    }
+
+    #[rstest]
+    #[case(3, 3)]
+    #[case(10, 6)]
+    fn test_stack_counter_per_stack(#[case] applications: u8, #[case] expected_stacks: u8) {
+        let target = &mut Champion::new_dummy_with_resist(100.0, 0.0);
+        let attacker = Champion::new_dummy();
+        for _ in 0..applications {
+            carve().upsert_to_champ(target, "Carve");
+        }
+        assert_eq!(expected_stacks, target.get_stacks("Carve"));
+        target.receive_damage(&attacker, 100.0);
+        let armor = 100.0 - 5.0 * expected_stacks as f64;
+        assert_relative_eq!(
+            100.0 * 100.0 / (100.0 + armor),
+            target.get_missing_health(),
+            epsilon = 1e-9
+        );
+
+        advance_time(7.0);
+        assert_eq!(0, target.get_stacks("Carve"));
+    }
+
+    #[rstest]
+    #[case(StackDecay::All, 6.5, 0)]
+    #[case(StackDecay::One, 6.5, 5)]
+    #[case(StackDecay::One, 30.5, 1)]
+    fn test_stack_counter_decay(
+        #[case] decay: StackDecay,
+        #[case] elapsed: f64,
+        #[case] expected_stacks: u8,
+    ) {
+        let target = &mut Champion::new_dummy();
+        for _ in 0..6 {
+            StackCounter { decay, ..carve() }.upsert_to_champ(target, "Carve");
+        }
+        advance_time(elapsed);
+        assert_eq!(expected_stacks, target.get_stacks("Carve"));
+    }
+
+    #[rstest]
+    #[case(StackReset::OnThreshold, 2, 0)]
+    #[case(StackReset::Never, 1, 4)]
+    fn test_stack_counter_threshold(
+        #[case] reset: StackReset,
+        #[case] expected_procs: u8,
+        #[case] expected_stacks: u8,
+    ) {
+        let target = &mut Champion::new_dummy();
+        for _ in 0..8 {
+            StackCounter {
+                reset,
+                ..StackCounter::on_nth_hit(
+                    4,
+                    StackApplyInfo {
+                        ttl: 10.0,
+                        unique_name: "Proc".to_string(),
+                        result: Box::new(EffectResult::StackCounter(StackCounter {
+                            increment: 1,
+                            max_stacks: 10,
+                            ttl: 10.0,
+                            reset: StackReset::Never,
+                            ..Default::default()
+                        })),
+                    },
+                    5.0,
+                )
+            }
+            .upsert_to_champ(target, "Counter");
+        }
+        assert_eq!(expected_procs, target.get_stacks("Proc"));
+        assert_eq!(expected_stacks, target.get_stacks("Counter"));
+    }
+
+    #[rstest]
+    #[case(1.0, 30.0, 20.0)]
+    #[case(5.0, 0.0, 0.0)]
+    fn test_stat_buff_expires(
+        #[case] elapsed: f64,
+        #[case] expected_armor: f64,
+        #[case] expected_magic_resist: f64,
+    ) {
+        let champion = &mut Champion::new_dummy();
+        champion.upsert_effect(EffectData {
+            unique_name: "Resist Buff".to_string(),
+            expiry: TIME.with(|time| *time.borrow() + 4.0),
+            result: EffectResult::StatBuff(StatBuff {
+                armor: 30.0,
+                magic_resist: 20.0,
+                ..Default::default()
+            }),
+        });
+        advance_time(elapsed);
+        assert_relative_eq!(expected_armor, champion.get_bonus_armor());
+        assert_relative_eq!(expected_magic_resist, champion.get_magic_resist());
+    }
+
+    #[rstest]
+    fn test_stacked_stat_buff() {
+        let champion = &mut Champion::new_dummy();
+        champion.stats.attack_speed = 0.625;
+        for _ in 0..3 {
+            StackCounter {
+                increment: 1,
+                max_stacks: 6,
+                ttl: 6.0,
+                per_stack: Some(Box::new(EffectResult::StatBuff(StatBuff {
+                    attack_speed: 5.0,
+                    ..Default::default()
+                }))),
+                reset: StackReset::Never,
+                ..Default::default()
+            }
+            .upsert_to_champ(champion, "Attack Speed Stacks");
+        }
+        assert_relative_eq!(15.0, champion.get_attack_speed().bonus);
+    }
+
+    #[rstest]
+    #[case(DotPolicy::Refresh, 10.0 + 40.0)]
+    #[case(DotPolicy::Stack, 40.0 + 40.0)]
+    fn test_damage_over_time_policy(#[case] policy: DotPolicy, #[case] expected: f64) {
+        let mut world = World::new();
+        let source = world.spawn(Champion::new_dummy());
+        let target = world.spawn(Champion::new_dummy());
+        let burn = || DamageOverTime::new(source, 40.0, DamageType::True, 4.0, 1.0, policy);
+        burn().upsert_to_champ(&mut world[target], "Burn");
+        world.advance_time(1.0);
+        burn().upsert_to_champ(&mut world[target], "Burn");
+        world.advance_time(10.0);
+        assert_relative_eq!(expected, world[target].get_missing_health());
     }
 }
@@ -1,3 +1,17 @@
 use std::cell::RefCell;
 
-thread_local! {pub static TIME: RefCell<f64> = RefCell::new(0.0)}
+thread_local! {pub static TIME: RefCell<f64> = const { RefCell::new(0.0) }}
+
+/**
+ * Moves the clock forward, `World::advance_time` also ticks the damage over time effects of its combatants
+ */
+pub fn advance_time(seconds: f64) {
+    TIME.with(|time| *time.borrow_mut() += seconds);
+}
+
+/**
+ * Puts the clock of the current thread back at 0, so a fresh simulation does not depend on the previous ones
+ */
+pub fn reset_time() {
+    TIME.with(|time| *time.borrow_mut() = 0.0);
+}
@@ -0,0 +1,309 @@
+use crate::{
+    champions::champion::Champion,
+    item_effects::ChampionApplyable,
+    load_wiki_item::{
+        apply_items_to_champ, load_wiki_item_cost, load_wiki_item_recipe, load_wiki_item_stats,
+    },
+};
+
+/**
+ * Total experience needed to reach each level, 280 for level 2 and 100 more for every level after
+ */
+pub fn level_from_experience(experience: f64) -> u8 {
+    let mut level = 1;
+    let mut needed = 280.0;
+    let mut remaining = experience;
+    while level < 18 && remaining >= needed {
+        remaining -= needed;
+        needed += 100.0;
+        level += 1;
+    }
+    return level;
+}
+
+/**
+ * Gold and experience granted every `interval` seconds starting at `start`, ie minion waves or a jungle camp
+ */
+#[derive(Clone, Debug)]
+pub struct IncomeSource {
+    pub start: f64,
+    pub interval: f64,
+    pub gold: f64,
+    pub experience: f64,
+}
+
+impl IncomeSource {
+    fn payouts_between(&self, from: f64, to: f64) -> u32 {
+        let paid_by = |time: f64| {
+            if time < self.start {
+                return 0;
+            }
+            return ((time - self.start) / self.interval).floor() as u32 + 1;
+        };
+        return paid_by(to) - paid_by(from);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct IncomeModel {
+    pub starting_gold: f64,
+    pub passive_gold_per_10: f64,
+    pub passive_gold_start: f64,
+    pub sources: Vec<IncomeSource>,
+}
+
+impl IncomeModel {
+    const MELEE_MINION: (f64, f64) = (21.0, 60.45);
+    const CASTER_MINION: (f64, f64) = (14.0, 29.76);
+    const SIEGE_MINION: (f64, f64) = (60.0, 93.6); // every third wave
+
+    /**
+     * Solo laner last hitting `cs_share` (0-1) of each minion wave, waves arrive in lane from 1:05 every 30 seconds
+     */
+    pub fn solo_lane(cs_share: f64) -> IncomeModel {
+        let wave = |(melee, caster, siege): ((f64, f64), (f64, f64), (f64, f64))| {
+            (
+                3.0 * melee.0 + 3.0 * caster.0 + siege.0 / 3.0,
+                3.0 * melee.1 + 3.0 * caster.1 + siege.1 / 3.0,
+            )
+        };
+        let (wave_gold, wave_experience) = wave((
+            IncomeModel::MELEE_MINION,
+            IncomeModel::CASTER_MINION,
+            IncomeModel::SIEGE_MINION,
+        ));
+        return IncomeModel {
+            starting_gold: 500.0,
+            passive_gold_per_10: 20.4,
+            passive_gold_start: 65.0,
+            sources: vec![IncomeSource {
+                start: 65.0,
+                interval: 30.0,
+                gold: cs_share * wave_gold,
+                // experience is shared by nearby minion deaths, not last hits
+                experience: wave_experience,
+            }],
+        };
+    }
+}
+
+/**
+ * Where the build is at a point of the game
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct TimelineSnapshot {
+    pub time: f64,
+    pub level: u8,
+    pub experience: f64,
+    pub total_gold: f64,
+    pub unspent_gold: f64,
+    pub items: Vec<String>,
+}
+
+impl ChampionApplyable for &TimelineSnapshot {
+    fn apply_to_champ(self, champion: &mut Champion) {
+        champion.set_level(self.level);
+        let item_names: Vec<&str> = self.items.iter().map(|item| item.as_str()).collect();
+        apply_items_to_champ(&item_names, champion);
+    }
+}
+
+/**
+ * Gold value of owned components used to build the item, removing them from `owned`.
+ * Components not owned are searched for their own components (ie long swords towards a duskblade)
+ */
+fn owned_component_value(item_name: &str, owned: &mut Vec<String>) -> f64 {
+    return load_wiki_item_recipe(item_name)
+        .iter()
+        .map(
+            |component| match owned.iter().position(|item| item == component) {
+                Some(index) => {
+                    owned.remove(index);
+                    load_wiki_item_cost(component).unwrap_or(0.0)
+                }
+                None => owned_component_value(component, owned),
+            },
+        )
+        .sum();
+}
+
+pub struct Timeline {
+    pub income: IncomeModel,
+    pub purchase_order: Vec<String>,
+}
+
+impl Timeline {
+    const TICK: f64 = 1.0;
+
+    pub fn new(income: IncomeModel, purchase_order: &[&str]) -> Timeline {
+        return Timeline {
+            income,
+            purchase_order: purchase_order.iter().map(|item| item.to_string()).collect(),
+        };
+    }
+
+    /**
+     * Plays the game forward a second at a time, buying the next item in order as soon as it is affordable
+     */
+    pub fn snapshot_at(&self, time: f64) -> TimelineSnapshot {
+        let mut snapshot = TimelineSnapshot {
+            time: 0.0,
+            level: 1,
+            experience: 0.0,
+            total_gold: self.income.starting_gold,
+            unspent_gold: self.income.starting_gold,
+            items: Vec::new(),
+        };
+        let mut next_purchase = 0;
+        loop {
+            while let Some(item_name) = self.purchase_order.get(next_purchase) {
+                let mut owned = snapshot.items.clone();
+                let cost = load_wiki_item_cost(item_name).unwrap_or(0.0)
+                    - owned_component_value(item_name, &mut owned);
+                if cost > snapshot.unspent_gold {
+                    break;
+                }
+                snapshot.unspent_gold -= cost;
+                owned.push(item_name.clone());
+                snapshot.items = owned;
+                next_purchase += 1;
+            }
+            if snapshot.time >= time {
+                break;
+            }
+            let from = snapshot.time;
+            let to = (from + Timeline::TICK).min(time);
+            let mut gold = 0.0;
+            if to > self.income.passive_gold_start {
+                let passive_seconds = to - from.max(self.income.passive_gold_start);
+                let gold_per_10 = self.income.passive_gold_per_10
+                    + snapshot
+                        .items
+                        .iter()
+                        .filter_map(|item| load_wiki_item_stats(item).gold_per_10)
+                        .sum::<f64>();
+                gold += gold_per_10 * passive_seconds / 10.0;
+            }
+            self.income.sources.iter().for_each(|source| {
+                let payouts = source.payouts_between(from, to) as f64;
+                gold += payouts * source.gold;
+                snapshot.experience += payouts * source.experience;
+            });
+            snapshot.total_gold += gold;
+            snapshot.unspent_gold += gold;
+            snapshot.level = level_from_experience(snapshot.experience);
+            snapshot.time = to;
+        }
+        return snapshot;
+    }
+
+    /**
+     * Evaluates the build at each timestamp, ie combo damage by minute for a power spike curve
+     */
+    pub fn evaluate<T>(
+        &self,
+        timestamps: &[f64],
+        evaluate: impl Fn(&TimelineSnapshot) -> T,
+    ) -> Vec<(TimelineSnapshot, T)> {
+        return timestamps
+            .iter()
+            .map(|time| {
+                let snapshot = self.snapshot_at(*time);
+                let value = evaluate(&snapshot);
+                (snapshot, value)
+            })
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.0, 1)]
+    #[case(279.0, 1)]
+    #[case(280.0, 2)]
+    #[case(280.0 + 380.0, 3)]
+    #[case(18360.0, 18)]
+    #[case(100000.0, 18)]
+    fn test_level_from_experience(#[case] experience: f64, #[case] expected: u8) {
+        assert_eq!(expected, level_from_experience(experience));
+    }
+
+    fn passive_only() -> IncomeModel {
+        IncomeModel {
+            starting_gold: 500.0,
+            passive_gold_per_10: 20.4,
+            passive_gold_start: 65.0,
+            sources: Vec::new(),
+        }
+    }
+
+    #[rstest]
+    #[case(0.0, 500.0)]
+    #[case(65.0, 500.0)]
+    #[case(75.0, 520.4)]
+    #[case(165.0, 704.0)]
+    fn test_passive_gold(#[case] time: f64, #[case] expected: f64) {
+        let snapshot = Timeline::new(passive_only(), &[]).snapshot_at(time);
+        assert_relative_eq!(expected, snapshot.total_gold, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_income_sources_pay_at_intervals() {
+        let income = IncomeModel {
+            sources: vec![IncomeSource {
+                start: 10.0,
+                interval: 30.0,
+                gold: 100.0,
+                experience: 280.0,
+            }],
+            passive_gold_per_10: 0.0,
+            ..passive_only()
+        };
+        let timeline = Timeline::new(income, &[]);
+        assert_eq!(1, timeline.snapshot_at(9.0).level);
+        let snapshot = timeline.snapshot_at(40.0);
+        assert_relative_eq!(700.0, snapshot.total_gold);
+        assert_eq!(2, snapshot.level);
+    }
+
+    #[rstest]
+    fn test_purchase_order_builds_from_components() {
+        let income = IncomeModel {
+            passive_gold_per_10: 500.0,
+            ..passive_only()
+        };
+        let timeline = Timeline::new(
+            income,
+            &["Long Sword", "Long Sword", "Serrated Dirk", "Cloth Armor"],
+        );
+        let start = timeline.snapshot_at(0.0);
+        assert_eq!(vec!["Long Sword".to_string()], start.items);
+        assert_relative_eq!(150.0, start.unspent_gold);
+
+        // dirk only costs its 400 combine cost once both long swords are owned
+        let snapshot = timeline.snapshot_at(65.0 + 15.0);
+        assert_eq!(vec!["Serrated Dirk".to_string()], snapshot.items);
+        assert_relative_eq!(500.0 + 750.0 - 350.0 - 350.0 - 400.0, snapshot.unspent_gold);
+    }
+
+    #[rstest]
+    fn test_gold_per_10_items() {
+        let timeline = Timeline::new(passive_only(), &["Spellthief's Edge"]);
+        let snapshot = timeline.snapshot_at(75.0);
+        assert_relative_eq!(500.0 + 20.4 + 2.0, snapshot.total_gold, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_evaluate_by_minute() {
+        let timeline = Timeline::new(IncomeModel::solo_lane(0.8), &["Long Sword"]);
+        let levels = timeline.evaluate(&[0.0, 300.0, 900.0], |snapshot| snapshot.level);
+        let levels: Vec<u8> = levels.into_iter().map(|(_, level)| level).collect();
+        assert_eq!(1, levels[0]);
+        assert!(levels[1] > levels[0] && levels[2] > levels[1]);
+    }
+}
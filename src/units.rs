@@ -0,0 +1,261 @@
+use serde::Deserialize;
+
+use crate::{
+    champions::champion::AbilityName,
+    load_champion::ChampionStats,
+    load_monster::{load_monster_stats, MonsterStats},
+};
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum MinionKind {
+    Melee,
+    Caster,
+    Siege,
+    Super,
+}
+
+struct MinionGrowth {
+    health: f64,
+    health_per_upgrade: f64,
+    attack_damage: f64,
+    attack_damage_per_upgrade: f64,
+    armor: f64,
+    magic_resist: f64,
+    attack_speed: f64,
+    attack_range: f64,
+}
+
+impl MinionKind {
+    const UPGRADE_INTERVAL: f64 = 90.0;
+    const MOVE_SPEED: f64 = 325.0;
+
+    // Source: https://leagueoflegends.fandom.com/wiki/Minion_(League_of_Legends), rounded
+    fn growth(&self) -> MinionGrowth {
+        return match self {
+            MinionKind::Melee => MinionGrowth {
+                health: 477.0,
+                health_per_upgrade: 22.0,
+                attack_damage: 12.0,
+                attack_damage_per_upgrade: 1.0,
+                armor: 0.0,
+                magic_resist: 0.0,
+                attack_speed: 1.25,
+                attack_range: 110.0,
+            },
+            MinionKind::Caster => MinionGrowth {
+                health: 296.0,
+                health_per_upgrade: 8.0,
+                attack_damage: 23.0,
+                attack_damage_per_upgrade: 1.5,
+                armor: 0.0,
+                magic_resist: 0.0,
+                attack_speed: 0.667,
+                attack_range: 550.0,
+            },
+            MinionKind::Siege => MinionGrowth {
+                health: 900.0,
+                health_per_upgrade: 50.0,
+                attack_damage: 41.0,
+                attack_damage_per_upgrade: 1.5,
+                armor: 30.0,
+                magic_resist: 0.0,
+                attack_speed: 1.0,
+                attack_range: 300.0,
+            },
+            MinionKind::Super => MinionGrowth {
+                health: 1600.0,
+                health_per_upgrade: 100.0,
+                attack_damage: 230.0,
+                attack_damage_per_upgrade: 5.0,
+                armor: 100.0,
+                magic_resist: -30.0,
+                attack_speed: 0.85,
+                attack_range: 170.0,
+            },
+        };
+    }
+
+    /**
+     * Minions upgrade every 90 seconds of game time rather than by level
+     */
+    pub fn stats_at(&self, game_time: f64) -> ChampionStats {
+        let growth = self.growth();
+        let upgrades = (game_time / MinionKind::UPGRADE_INTERVAL).floor().max(0.0);
+        return ChampionStats {
+            health: growth.health + upgrades * growth.health_per_upgrade,
+            base_attack_damage: growth.attack_damage + upgrades * growth.attack_damage_per_upgrade,
+            armor: growth.armor,
+            magic_resist: growth.magic_resist,
+            attack_speed: growth.attack_speed,
+            attack_range: growth.attack_range,
+            move_speed: MinionKind::MOVE_SPEED,
+            ..Default::default()
+        };
+    }
+}
+
+/**
+ * Monsters as keyed in data/monsters.json
+ */
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, Deserialize)]
+pub enum MonsterKind {
+    Gromp,
+    BlueSentinel,
+    RedBrambleback,
+    GreaterMurkWolf,
+    MurkWolf,
+    CrimsonRaptor,
+    Raptor,
+    AncientKrug,
+    Krug,
+    RiftScuttler,
+    Dragon,
+    RiftHerald,
+    BaronNashor,
+}
+
+impl MonsterKind {
+    pub const ALL: [MonsterKind; 13] = [
+        MonsterKind::Gromp,
+        MonsterKind::BlueSentinel,
+        MonsterKind::RedBrambleback,
+        MonsterKind::GreaterMurkWolf,
+        MonsterKind::MurkWolf,
+        MonsterKind::CrimsonRaptor,
+        MonsterKind::Raptor,
+        MonsterKind::AncientKrug,
+        MonsterKind::Krug,
+        MonsterKind::RiftScuttler,
+        MonsterKind::Dragon,
+        MonsterKind::RiftHerald,
+        MonsterKind::BaronNashor,
+    ];
+
+    pub fn name(&self) -> String {
+        return format!("{:?}", self);
+    }
+
+    pub fn is_epic(&self) -> bool {
+        matches!(
+            self,
+            MonsterKind::Dragon | MonsterKind::RiftHerald | MonsterKind::BaronNashor
+        )
+    }
+
+    pub fn stats(&self) -> MonsterStats {
+        return load_monster_stats(self.name());
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum TurretKind {
+    Outer,
+    Inner,
+    Inhibitor,
+    Nexus,
+}
+
+impl TurretKind {
+    // Source: https://leagueoflegends.fandom.com/wiki/Turret, plates and fortification left out
+    pub fn stats(&self) -> ChampionStats {
+        let (health, resists, attack_damage) = match self {
+            TurretKind::Outer => (5000.0, 40.0, 152.0),
+            TurretKind::Inner => (3600.0, 55.0, 170.0),
+            TurretKind::Inhibitor => (3300.0, 55.0, 170.0),
+            TurretKind::Nexus => (2700.0, 55.0, 150.0),
+        };
+        return ChampionStats {
+            health,
+            armor: resists,
+            magic_resist: resists,
+            base_attack_damage: attack_damage,
+            attack_speed: 0.833,
+            attack_range: 750.0,
+            ..Default::default()
+        };
+    }
+}
+
+/**
+ * What a combatant is, deciding its stat growth and the damage rules it follows
+ */
+#[derive(Default, Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum UnitKind {
+    #[default]
+    Champion,
+    Minion(MinionKind),
+    Monster(MonsterKind),
+    Turret(TurretKind),
+}
+
+impl UnitKind {
+    pub fn is_monster(&self) -> bool {
+        matches!(self, UnitKind::Monster(_))
+    }
+
+    /**
+     * Units that on-hit caps apply to, ie mist's edge's maximum of 60
+     */
+    pub fn is_minion_or_monster(&self) -> bool {
+        matches!(self, UnitKind::Minion(_) | UnitKind::Monster(_))
+    }
+
+    /**
+     * Structures only take damage from basic attacks and the spellblades they trigger
+     */
+    pub fn is_immune_to(&self, name: &AbilityName) -> bool {
+        return match self {
+            UnitKind::Turret(_) => !(matches!(name, AbilityName::AUTO) || name.is_spellblade()),
+            _ => false,
+        };
+    }
+
+    /**
+     * Percent less damage taken from champions
+     */
+    pub fn damage_reduction(&self) -> f64 {
+        return match self {
+            UnitKind::Monster(monster) => monster.stats().damage_reduction,
+            _ => 0.0,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0.0, 477.0)]
+    #[case(89.0, 477.0)]
+    #[case(90.0, 499.0)]
+    #[case(900.0, 697.0)]
+    fn test_minion_health_upgrades(#[case] game_time: f64, #[case] expected: f64) {
+        assert_eq!(expected, MinionKind::Melee.stats_at(game_time).health);
+    }
+
+    #[rstest]
+    fn test_every_monster_has_stats() {
+        MonsterKind::ALL.iter().for_each(|monster| {
+            assert!(monster.stats().health > 0.0, "{:?}", monster);
+        });
+    }
+
+    #[rstest]
+    #[case(UnitKind::Turret(TurretKind::Outer), AbilityName::Q, true)]
+    #[case(UnitKind::Turret(TurretKind::Outer), AbilityName::AUTO, false)]
+    #[case(
+        UnitKind::Turret(TurretKind::Outer),
+        AbilityName::SpellbladeSheen,
+        false
+    )]
+    #[case(UnitKind::Monster(MonsterKind::Gromp), AbilityName::Q, false)]
+    fn test_is_immune_to(
+        #[case] kind: UnitKind,
+        #[case] name: AbilityName,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(expected, kind.is_immune_to(&name));
+    }
+}
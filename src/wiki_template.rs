@@ -0,0 +1,742 @@
+use std::collections::HashMap;
+
+use crate::target::DamageType;
+
+/**
+ * Parses the template expressions used in wiki item descriptions (ie `{{as|{{rd|75|55}} '''bonus''' damage}}`)
+ * into numbers we can evaluate, so a patch update only needs a refreshed wiki_items.json
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum WikiNode {
+    Text(String),
+    Template(WikiTemplate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiTemplate {
+    pub name: String,
+    pub positional: Vec<Vec<WikiNode>>,
+    pub named: HashMap<String, Vec<WikiNode>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelScaling {
+    pub values: Vec<f64>,
+    pub levels: Vec<u8>, // level at which the matching value starts applying
+    pub scaling_type: Option<String>, // None is the holder's level, otherwise ie "target's level"
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WikiQuantity {
+    Flat(f64),
+    RangeDependent { melee: f64, ranged: f64 },
+    LevelScaling(LevelScaling),
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy)]
+pub enum ScalingStat {
+    BaseAd,
+    BonusAd,
+    TotalAd,
+    AbilityPower,
+    MaxHealth,
+    BonusHealth,
+    MissingHealth,
+    Armor,
+    BonusArmor,
+    MagicResist,
+    BonusMagicResist,
+    TargetMaxHealth,
+    TargetCurrentHealth,
+    TargetMissingHealth,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WikiTerm {
+    pub quantity: WikiQuantity,
+    pub is_percent: bool,
+    pub stat: Option<ScalingStat>,
+    pub following_text: String,
+}
+
+/**
+ * Values a term can scale with, filled in by whoever evaluates it
+ */
+#[derive(Default, Debug, Clone)]
+pub struct ScalingValues {
+    pub is_ranged: bool,
+    pub level: u8,
+    pub base_ad: f64,
+    pub bonus_ad: f64,
+    pub ability_power: f64,
+    pub max_health: f64,
+    pub bonus_health: f64,
+    pub missing_health: f64,
+    pub armor: f64,
+    pub bonus_armor: f64,
+    pub magic_resist: f64,
+    pub bonus_magic_resist: f64,
+    pub target_max_health: f64,
+    pub target_current_health: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Quantity(WikiQuantity),
+    Boundary, // end of a template argument, text after it doesn't describe the previous quantity
+}
+
+impl LevelScaling {
+    pub fn value_at(&self, level: u8) -> f64 {
+        let index = self
+            .levels
+            .iter()
+            .rposition(|start| *start <= level)
+            .unwrap_or(0);
+        return self.values[index.min(self.values.len() - 1)];
+    }
+}
+
+impl WikiQuantity {
+    pub fn value(&self, is_ranged: bool, level: u8) -> f64 {
+        return match self {
+            WikiQuantity::Flat(value) => *value,
+            WikiQuantity::RangeDependent { melee, ranged } => {
+                if is_ranged {
+                    *ranged
+                } else {
+                    *melee
+                }
+            }
+            WikiQuantity::LevelScaling(scaling) => scaling.value_at(level),
+        };
+    }
+}
+
+impl ScalingValues {
+    pub fn stat_value(&self, stat: &ScalingStat) -> f64 {
+        return match stat {
+            ScalingStat::BaseAd => self.base_ad,
+            ScalingStat::BonusAd => self.bonus_ad,
+            ScalingStat::TotalAd => self.base_ad + self.bonus_ad,
+            ScalingStat::AbilityPower => self.ability_power,
+            ScalingStat::MaxHealth => self.max_health,
+            ScalingStat::BonusHealth => self.bonus_health,
+            ScalingStat::MissingHealth => self.missing_health,
+            ScalingStat::Armor => self.armor,
+            ScalingStat::BonusArmor => self.bonus_armor,
+            ScalingStat::MagicResist => self.magic_resist,
+            ScalingStat::BonusMagicResist => self.bonus_magic_resist,
+            ScalingStat::TargetMaxHealth => self.target_max_health,
+            ScalingStat::TargetCurrentHealth => self.target_current_health,
+            ScalingStat::TargetMissingHealth => self.target_max_health - self.target_current_health,
+        };
+    }
+}
+
+impl WikiTerm {
+    /**
+     * Flat terms evaluate to their value, ratios to their share of the scaling stat.
+     * None for percentages that don't scale with a stat we know of (ie slow amounts)
+     */
+    pub fn evaluate(&self, values: &ScalingValues) -> Option<f64> {
+        let amount = self.quantity.value(values.is_ranged, values.level);
+        if !self.is_percent {
+            return Some(amount);
+        }
+        return self
+            .stat
+            .map(|stat| 0.01 * amount * values.stat_value(&stat));
+    }
+}
+
+pub fn evaluate_terms(terms: &[WikiTerm], values: &ScalingValues) -> f64 {
+    terms.iter().filter_map(|term| term.evaluate(values)).sum()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn starts_with(&self, pattern: &str) -> bool {
+        return pattern
+            .chars()
+            .enumerate()
+            .all(|(offset, c)| self.chars.get(self.pos + offset) == Some(&c));
+    }
+
+    fn parse_sequence(&mut self, in_template: bool) -> Vec<WikiNode> {
+        let mut nodes = Vec::new();
+        let mut text = String::new();
+        while self.pos < self.chars.len() {
+            if in_template && (self.starts_with("|") || self.starts_with("}}")) {
+                break;
+            }
+            if self.starts_with("{{") {
+                if !text.is_empty() {
+                    nodes.push(WikiNode::Text(std::mem::take(&mut text)));
+                }
+                nodes.push(WikiNode::Template(self.parse_template()));
+            } else if self.starts_with("[[") {
+                text.push_str(&self.parse_link());
+            } else {
+                text.push(self.chars[self.pos]);
+                self.pos += 1;
+            }
+        }
+        if !text.is_empty() {
+            nodes.push(WikiNode::Text(text));
+        }
+        return nodes;
+    }
+
+    /**
+     * [[target|label]] links become their displayed label
+     */
+    fn parse_link(&mut self) -> String {
+        self.pos += 2;
+        let mut inner = String::new();
+        while self.pos < self.chars.len() && !self.starts_with("]]") {
+            inner.push(self.chars[self.pos]);
+            self.pos += 1;
+        }
+        self.pos += 2;
+        return inner.rsplit('|').next().unwrap_or_default().to_string();
+    }
+
+    fn parse_template(&mut self) -> WikiTemplate {
+        self.pos += 2;
+        let name = nodes_to_text(&self.parse_sequence(true)).trim().to_string();
+        let mut template = WikiTemplate {
+            name,
+            positional: Vec::new(),
+            named: HashMap::new(),
+        };
+        while self.starts_with("|") {
+            self.pos += 1;
+            let mut arg = self.parse_sequence(true);
+            match split_named_arg(&mut arg) {
+                Some(key) => {
+                    template.named.insert(key, arg);
+                }
+                None => template.positional.push(arg),
+            }
+        }
+        self.pos += 2;
+        return template;
+    }
+}
+
+/**
+ * Strips the `key=` prefix off named arguments, returning the key
+ */
+fn split_named_arg(arg: &mut [WikiNode]) -> Option<String> {
+    if let Some(WikiNode::Text(first)) = arg.first_mut() {
+        let (key, rest) = first.split_once('=')?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return None;
+        }
+        let key = key.to_string();
+        *first = rest.to_string();
+        return Some(key);
+    }
+    return None;
+}
+
+pub fn parse_wiki_text(input: &str) -> Vec<WikiNode> {
+    let mut parser = Parser {
+        chars: input.chars().collect(),
+        pos: 0,
+    };
+    return parser.parse_sequence(false);
+}
+
+/**
+ * Display text of the nodes, templates contribute their first argument
+ */
+pub fn nodes_to_text(nodes: &[WikiNode]) -> String {
+    return nodes
+        .iter()
+        .map(|node| match node {
+            WikiNode::Text(text) => text.clone(),
+            WikiNode::Template(template) => template
+                .positional
+                .first()
+                .map(|arg| nodes_to_text(arg))
+                .unwrap_or_default(),
+        })
+        .collect();
+}
+
+/**
+ * Evaluates simple wiki arithmetic like `100*1.8` or `180/3`
+ */
+fn parse_number(input: &str) -> Option<f64> {
+    let input = input.trim();
+    if let Some((l, r)) = input.split_once('*') {
+        return Some(parse_number(l)? * parse_number(r)?);
+    }
+    if let Some((l, r)) = input.split_once('/') {
+        return Some(parse_number(l)? / parse_number(r)?);
+    }
+    return input.parse().ok();
+}
+
+/**
+ * Parses `a to b` ranges (optionally `for n` values or `by step`) and `;` separated lists
+ */
+fn parse_series(input: &str, default_count: usize) -> Option<Vec<f64>> {
+    let mut out = Vec::new();
+    for part in input.split(';') {
+        let part = part.trim();
+        let (range, count) = match part.split_once(" for ") {
+            Some((range, count)) => (range, Some(count.trim().parse::<usize>().ok()?)),
+            None => (part, None),
+        };
+        let (range, step) = match range.split_once(" by ") {
+            Some((range, step)) => (range, Some(parse_number(step)?)),
+            None => (range, None),
+        };
+        match range.split_once(" to ") {
+            Some((start, end)) => {
+                let (start, end) = (parse_number(start)?, parse_number(end)?);
+                let count = match (count, step) {
+                    (Some(count), _) => count,
+                    (None, Some(step)) => ((end - start) / step).abs().round() as usize + 1,
+                    (None, None) => default_count,
+                };
+                if count < 2 {
+                    out.push(start);
+                    continue;
+                }
+                for i in 0..count {
+                    out.push(start + (end - start) * (i as f64) / ((count - 1) as f64));
+                }
+            }
+            None => out.push(parse_number(range)?),
+        }
+    }
+    return Some(out);
+}
+
+/**
+ * Level breakpoints like `1;9 to 18`, ranges always step by one level unless a `by` is given
+ */
+fn parse_levels(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    for part in input.split(';') {
+        let part = part.split(" for ").next()?.trim();
+        let (range, step) = match part.split_once(" by ") {
+            Some((range, step)) => (range, step.trim().parse::<u8>().ok()?),
+            None => (part, 1),
+        };
+        match range.split_once(" to ") {
+            Some((start, end)) => {
+                let (start, end) = (
+                    start.trim().parse::<u8>().ok()?,
+                    end.trim().parse::<u8>().ok()?,
+                );
+                out.extend((start..=end).step_by(step.max(1) as usize));
+            }
+            None => out.push(range.parse().ok()?),
+        }
+    }
+    return Some(out);
+}
+
+fn parse_level_scaling(template: &WikiTemplate) -> Option<LevelScaling> {
+    let values_text = nodes_to_text(template.positional.first()?);
+    let levels_text = template.positional.get(1).map(|arg| nodes_to_text(arg));
+    let levels = levels_text.as_deref().and_then(parse_levels);
+    let default_count = levels.as_ref().map(|levels| levels.len()).unwrap_or(18);
+    let values = parse_series(&values_text, default_count)?;
+    let levels = levels
+        .filter(|levels| levels.len() == values.len())
+        .unwrap_or_else(|| {
+            // spread over levels 1-18 when no breakpoints are given
+            (0..values.len())
+                .map(|i| 1 + (i * 18 / values.len()) as u8)
+                .collect()
+        });
+    let scaling_type = template
+        .named
+        .get("type")
+        .map(|nodes| strip_markup(&nodes_to_text(nodes)).trim().to_string());
+    return Some(LevelScaling {
+        values,
+        levels,
+        scaling_type,
+    });
+}
+
+fn strip_markup(text: &str) -> String {
+    text.replace("'''", "").replace("''", "")
+}
+
+/**
+ * First number in the argument, and whether it is a percentage
+ */
+fn first_quantity(arg: &[WikiNode]) -> Option<(f64, bool)> {
+    let tokens = tokenize(arg);
+    let index = tokens
+        .iter()
+        .position(|token| matches!(token, Token::Quantity(_)))?;
+    let value = match &tokens[index] {
+        Token::Quantity(quantity) => quantity.value(false, 1),
+        _ => return None,
+    };
+    let is_percent =
+        matches!(tokens.get(index + 1), Some(Token::Text(text)) if text.starts_with('%'));
+    return Some((value, is_percent));
+}
+
+fn tokenize_text(text: &str, out: &mut Vec<Token>) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut current = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let starts_number = chars[i].is_ascii_digit()
+            && (i == 0 || !(chars[i - 1].is_alphanumeric() || chars[i - 1] == '.'));
+        if !starts_number {
+            current.push(chars[i]);
+            i += 1;
+            continue;
+        }
+        // a second '.' ends the number, ie the patch "1.2.3" is 1.2 followed by text
+        let mut number = String::new();
+        while i < chars.len()
+            && (chars[i].is_ascii_digit()
+                || (chars[i] == '.'
+                    && !number.contains('.')
+                    && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())))
+        {
+            number.push(chars[i]);
+            i += 1;
+        }
+        if !current.is_empty() {
+            out.push(Token::Text(std::mem::take(&mut current)));
+        }
+        out.push(Token::Quantity(WikiQuantity::Flat(number.parse().unwrap())));
+    }
+    if !current.is_empty() {
+        out.push(Token::Text(current));
+    }
+}
+
+fn tokenize_into(nodes: &[WikiNode], out: &mut Vec<Token>) {
+    for node in nodes {
+        match node {
+            WikiNode::Text(text) => tokenize_text(text, out),
+            WikiNode::Template(template) => tokenize_template(template, out),
+        }
+    }
+}
+
+fn tokenize_template(template: &WikiTemplate, out: &mut Vec<Token>) {
+    match template.name.as_str() {
+        "fd" | "g" => {
+            if let Some(arg) = template.positional.first() {
+                if let Some(value) = parse_number(&nodes_to_text(arg)) {
+                    out.push(Token::Quantity(WikiQuantity::Flat(value)));
+                }
+            }
+        }
+        "rd" => {
+            let melee = template
+                .positional
+                .first()
+                .and_then(|arg| first_quantity(arg));
+            let ranged = template
+                .positional
+                .get(1)
+                .and_then(|arg| first_quantity(arg));
+            match (melee, ranged) {
+                (Some((melee, is_percent)), Some((ranged, _))) => {
+                    out.push(Token::Quantity(WikiQuantity::RangeDependent {
+                        melee,
+                        ranged,
+                    }));
+                    if is_percent {
+                        out.push(Token::Text("%".to_string()));
+                    }
+                }
+                _ => {
+                    if let Some(arg) = template.positional.first() {
+                        tokenize_into(arg, out);
+                    }
+                }
+            }
+        }
+        "pp" => {
+            if let Some(scaling) = parse_level_scaling(template) {
+                out.push(Token::Quantity(WikiQuantity::LevelScaling(scaling)));
+                if template.named.contains_key("key") {
+                    out.push(Token::Text("%".to_string()));
+                }
+            }
+        }
+        "tip" => {
+            // {{tip|keyword|display text}}
+            if let Some(arg) = template.positional.last() {
+                out.push(Token::Text(nodes_to_text(arg)));
+            }
+        }
+        _ => {
+            if let Some(arg) = template.positional.first() {
+                tokenize_into(arg, out);
+            }
+            out.push(Token::Boundary);
+        }
+    }
+}
+
+fn tokenize(nodes: &[WikiNode]) -> Vec<Token> {
+    let mut raw = Vec::new();
+    tokenize_into(nodes, &mut raw);
+    // merge neighbouring text so a quantity sees everything up to the next quantity / boundary
+    let mut out: Vec<Token> = Vec::new();
+    for token in raw {
+        match (out.last_mut(), token) {
+            (Some(Token::Text(previous)), Token::Text(text)) => previous.push_str(&text),
+            (_, token) => out.push(token),
+        }
+    }
+    return out;
+}
+
+fn has_word(text: &str, word: &str) -> bool {
+    text.split(|c: char| !c.is_alphanumeric())
+        .any(|candidate| candidate == word)
+}
+
+fn classify_stat(text: &str) -> Option<ScalingStat> {
+    let text = strip_markup(text).to_lowercase();
+    if text.contains("target") {
+        if text.contains("maximum health") {
+            return Some(ScalingStat::TargetMaxHealth);
+        }
+        if text.contains("current health") {
+            return Some(ScalingStat::TargetCurrentHealth);
+        }
+        if text.contains("missing health") {
+            return Some(ScalingStat::TargetMissingHealth);
+        }
+    }
+    let candidates = [
+        ("missing health", ScalingStat::MissingHealth),
+        ("bonus health", ScalingStat::BonusHealth),
+        ("maximum health", ScalingStat::MaxHealth),
+        ("base ad", ScalingStat::BaseAd),
+        ("bonus ad", ScalingStat::BonusAd),
+        ("bonus armor", ScalingStat::BonusArmor),
+        ("bonus magic resist", ScalingStat::BonusMagicResist),
+        ("magic resist", ScalingStat::MagicResist),
+    ];
+    if let Some((_, stat)) = candidates.iter().find(|(key, _)| text.contains(key)) {
+        return Some(*stat);
+    }
+    if has_word(&text, "ad") {
+        return Some(ScalingStat::TotalAd);
+    }
+    if has_word(&text, "ap") {
+        return Some(ScalingStat::AbilityPower);
+    }
+    if has_word(&text, "armor") {
+        return Some(ScalingStat::Armor);
+    }
+    return None;
+}
+
+fn terms_from_tokens(tokens: &[Token]) -> Vec<WikiTerm> {
+    let mut out = Vec::new();
+    for (index, token) in tokens.iter().enumerate() {
+        if let Token::Quantity(quantity) = token {
+            let following_text = match tokens.get(index + 1) {
+                Some(Token::Text(text)) => text.clone(),
+                _ => String::new(),
+            };
+            let is_percent = following_text.starts_with('%');
+            let stat = if is_percent {
+                classify_stat(&following_text)
+            } else {
+                None
+            };
+            out.push(WikiTerm {
+                quantity: quantity.clone(),
+                is_percent,
+                stat,
+                following_text,
+            });
+        }
+    }
+    return out;
+}
+
+/**
+ * Every number in the description along with what it scales with
+ */
+pub fn parse_terms(description: &str) -> Vec<WikiTerm> {
+    terms_from_tokens(&tokenize(&parse_wiki_text(description)))
+}
+
+/**
+ * Terms of the damage clause, ie everything after "deals" up to the damage type
+ * ("deals 100% base AD bonus physical damage")
+ */
+pub fn parse_damage_terms(description: &str) -> Vec<WikiTerm> {
+    let tokens = tokenize(&parse_wiki_text(description));
+    let is_deal = |token: &Token| matches!(token, Token::Text(text) if ["deal", "deals", "dealing"].iter().any(|word| has_word(&text.to_lowercase(), word)));
+    let mut start = match tokens.iter().position(is_deal) {
+        Some(start) => start + 1,
+        None => return Vec::new(),
+    };
+    let mut seen_quantity = false;
+    let mut end = tokens.len();
+    for (index, token) in tokens.iter().enumerate().skip(start) {
+        match token {
+            Token::Quantity(_) => seen_quantity = true,
+            Token::Text(text) if seen_quantity && text.to_lowercase().contains("damage") => {
+                end = index + 1;
+                break;
+            }
+            // a later deal starts the clause again, ie sunfire's "dealing damage activates this passive for 3 seconds. Deal 15"
+            token if is_deal(token) => {
+                start = index + 1;
+                seen_quantity = false;
+            }
+            _ => {}
+        }
+    }
+    return terms_from_tokens(&tokens[start..end]);
+}
+
+/**
+ * Type of the first damage mentioned, ie "magic" in lich bane's "deals 75% base AD (+ 50% AP) bonus magic damage"
+ */
+pub fn parse_damage_type(description: &str) -> Option<DamageType> {
+    let text = nodes_to_text(&parse_wiki_text(description)).to_lowercase();
+    return [
+        ("physical damage", DamageType::Physical),
+        ("magic damage", DamageType::Magic),
+        ("true damage", DamageType::True),
+    ]
+    .into_iter()
+    .filter_map(|(needle, damage_type)| text.find(needle).map(|index| (index, damage_type)))
+    .min_by_key(|(index, _)| *index)
+    .map(|(_, damage_type)| damage_type);
+}
+
+/**
+ * Cooldowns are either a plain number or a template followed by a remark, ie "{{fd|1.5}} (begins after ...)"
+ */
+pub fn parse_cooldown(cd: &str) -> Option<WikiQuantity> {
+    return tokenize(&parse_wiki_text(cd))
+        .into_iter()
+        .find_map(|token| match token {
+            Token::Quantity(quantity) => Some(quantity),
+            _ => None,
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    const NIGHTSTALKER: &str = "Your next basic attack against an enemy champion deals {{as|{{rd|75|55}} {{as|(+ {{rd|30%|25%}} '''bonus''' AD)}} '''bonus''' physical damage|physical damage}} [[on-hit]] and {{tip|slow|slows}} the target by 99% for {{fd|0.25}} seconds.";
+
+    #[rstest]
+    fn test_parse_nightstalker() {
+        let terms = parse_terms(NIGHTSTALKER);
+        assert_eq!(4, terms.len(), "{:#?}", terms);
+        assert_eq!(
+            WikiQuantity::RangeDependent {
+                melee: 75.0,
+                ranged: 55.0
+            },
+            terms[0].quantity
+        );
+        assert!(!terms[0].is_percent);
+        assert_eq!(Some(ScalingStat::BonusAd), terms[1].stat);
+        assert!(terms[1].is_percent);
+        assert_eq!(None, terms[2].stat); // the 99% slow
+        assert_eq!(WikiQuantity::Flat(0.25), terms[3].quantity);
+
+        let damage_terms = parse_damage_terms(NIGHTSTALKER);
+        assert_eq!(terms[0..2], damage_terms[..]);
+        let values = ScalingValues {
+            bonus_ad: 100.0,
+            ..Default::default()
+        };
+        assert_relative_eq!(105.0, evaluate_terms(&damage_terms, &values));
+    }
+
+    #[rstest]
+    fn test_parse_dotted_version() {
+        let terms = parse_terms("changed in patch 1.2.3 to deal 10 damage");
+        let values: Vec<f64> = terms
+            .iter()
+            .map(|term| term.quantity.value(false, 1))
+            .collect();
+        assert_eq!(vec![1.2, 10.0], values);
+    }
+
+    #[rstest]
+    #[case("After using an [[champion ability|ability]], your next basic attack within 10 seconds deals {{as|125% '''base''' AD}} {{as|(+ {{rd|6%|3%}} of target's '''maximum''' health)}} as {{as|'''bonus''' physical damage}} [[on-hit]], for a '''minimum''' of {{as|150% '''base''' AD}}", &[(125.0, Some(ScalingStat::BaseAd)), (6.0, Some(ScalingStat::TargetMaxHealth))])]
+    #[case("your next basic attack within 10 seconds deals {{as|75% '''base''' AD}} {{as|(+ 50% AP)}} {{as|'''bonus''' magic damage}} [[on-hit]].", &[(75.0, Some(ScalingStat::BaseAd)), (50.0, Some(ScalingStat::AbilityPower))])]
+    #[case("deals {{as|100% '''base''' AD (+ 40% '''bonus''' AD)}} {{as|'''bonus''' physical damage}} and restores {{as|mana}} equal to {{as|40% '''base''' AD}}", &[(100.0, Some(ScalingStat::BaseAd)), (40.0, Some(ScalingStat::BonusAd))])]
+    #[case("Taking or dealing damage activates this passive for 3 seconds. Deal {{as|15 {{as|(+ {{fd|1.75}}% '''bonus''' health)}} magic damage|magic damage}} every second", &[(15.0, None), (1.75, Some(ScalingStat::BonusHealth))])]
+    fn test_parse_damage_ratios(
+        #[case] description: &str,
+        #[case] expected: &[(f64, Option<ScalingStat>)],
+    ) {
+        let terms: Vec<(f64, Option<ScalingStat>)> = parse_damage_terms(description)
+            .iter()
+            .map(|term| (term.quantity.value(false, 1), term.stat))
+            .collect();
+        assert_eq!(expected, &terms[..]);
+    }
+
+    #[rstest]
+    #[case("{{pp|100 to 180}}", 1, 100.0)]
+    #[case("{{pp|100 to 180}}", 18, 180.0)]
+    #[case("{{pp|40 to 103 for 10|1;10 to 18}}", 9, 40.0)]
+    #[case("{{pp|40 to 103 for 10|1;10 to 18}}", 11, 54.0)]
+    #[case(
+        "{{pp|15;25;35;45;55;65;75;76.25;77.5;78.75;80|1;9 to 18 for 10}}",
+        16,
+        77.5
+    )]
+    #[case("{{pp|180/3 to 315/3|1;10 to 18 by 1}}", 1, 60.0)]
+    fn test_parse_level_scaling(#[case] text: &str, #[case] level: u8, #[case] expected: f64) {
+        let terms = parse_terms(text);
+        assert_eq!(1, terms.len());
+        assert_relative_eq!(expected, terms[0].quantity.value(false, level));
+    }
+
+    #[rstest]
+    #[case("15", false, 15.0)]
+    #[case("{{fd|1.5}} (begins after using the empowered attack)", false, 1.5)]
+    #[case("{{rd|6|12}}", true, 12.0)]
+    #[case("{{pp|120 to 60|type=average champion level}}", false, 120.0)]
+    fn test_parse_cooldown(#[case] cd: &str, #[case] is_ranged: bool, #[case] expected: f64) {
+        assert_relative_eq!(expected, parse_cooldown(cd).unwrap().value(is_ranged, 1));
+    }
+
+    #[rstest]
+    #[case("deals {{as|75% '''base''' AD}} {{as|(+ 50% AP)}} {{as|'''bonus''' magic damage}} [[on-hit]]", Some(DamageType::Magic))]
+    #[case(
+        "deals {{as|200% '''base''' AD}} {{as|'''bonus''' physical damage}}",
+        Some(DamageType::Physical)
+    )]
+    #[case("deals 10 true damage, then 20 magic damage", Some(DamageType::True))]
+    #[case("restores mana", None)]
+    fn test_parse_damage_type(#[case] description: &str, #[case] expected: Option<DamageType>) {
+        assert_eq!(expected, parse_damage_type(description));
+    }
+}
@@ -0,0 +1,381 @@
+use std::ops::{Index, IndexMut};
+
+use crate::{
+    champions::{
+        champion::{AbilityName, CastingData, Champion},
+        skill_order::Skill,
+    },
+    item_effects::{OnHitActivation, STATIC_ABILITIES},
+    runes::process_rune_hooks,
+    stat_sheet::HitSheets,
+    target::{AbilityEffect, DamageType, EffectData, EffectResult, EmpowerState, Target},
+    time_manager::{advance_time, TIME},
+};
+
+/**
+ * Handle to a combatant of a `World`, valid for as long as the world is
+ */
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Copy, PartialOrd, Ord)]
+pub struct EntityId(usize);
+
+/**
+ * Arena owning every combatant of a simulation. Abilities receive the world along with their caster and
+ * target ids, so effects reach any combatant without shared ownership
+ */
+#[derive(Default)]
+pub struct World {
+    entities: Vec<Champion>,
+}
+
+impl Index<EntityId> for World {
+    type Output = Champion;
+    fn index(&self, id: EntityId) -> &Champion {
+        &self.entities[id.0]
+    }
+}
+
+impl IndexMut<EntityId> for World {
+    fn index_mut(&mut self, id: EntityId) -> &mut Champion {
+        &mut self.entities[id.0]
+    }
+}
+
+impl World {
+    pub fn new() -> World {
+        World::default()
+    }
+
+    pub fn spawn(&mut self, champion: Champion) -> EntityId {
+        self.entities.push(champion);
+        return EntityId(self.entities.len() - 1);
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = EntityId> {
+        (0..self.entities.len()).map(EntityId)
+    }
+
+    /**
+     * Moves the clock forward, ticking the damage over time effects on everyone
+     */
+    pub fn advance_time(&mut self, seconds: f64) {
+        advance_time(seconds);
+        let ids: Vec<EntityId> = self.ids().collect();
+        ids.into_iter().for_each(|id| self.tick_effects(id));
+    }
+
+    /**
+     * Deals the damage of every damage over time tick due on the entity by the current time
+     */
+    pub fn tick_effects(&mut self, id: EntityId) {
+        for (source, damage, damage_type) in self[id].take_due_damage() {
+            self.deal_typed_damage(source, id, damage, damage_type);
+        }
+    }
+
+    pub fn deal_damage(&mut self, attacker: EntityId, target: EntityId, damage: f64) {
+        self.deal_typed_damage(attacker, target, damage, DamageType::Physical)
+    }
+
+    /**
+     * Damage after the target's resists and the attacker's penetration and amplifiers, the attacker may be the target
+     */
+    pub fn deal_typed_damage(
+        &mut self,
+        attacker: EntityId,
+        target: EntityId,
+        damage: f64,
+        damage_type: DamageType,
+    ) {
+        let sheets = HitSheets::new(&self[attacker], &self[target]);
+        self.deal_typed_damage_with(attacker, target, &sheets, damage, damage_type);
+    }
+
+    /**
+     * Same as `deal_typed_damage`, with the sheets the ability already built to compute the damage
+     */
+    pub fn deal_typed_damage_with(
+        &mut self,
+        attacker: EntityId,
+        target: EntityId,
+        sheets: &HitSheets,
+        damage: f64,
+        damage_type: DamageType,
+    ) {
+        let attacker = &self[attacker];
+        let mitigated = self[target].mitigated_damage_with(attacker, sheets, damage, damage_type)
+            * (1.0 - attacker.damage_reduction_against(target) / 100.0);
+        let execute_threshold = attacker.execute_threshold();
+        self[target].take_mitigated_damage(mitigated, execute_threshold);
+    }
+
+    pub fn execute_combo(
+        &mut self,
+        attacker: EntityId,
+        combo: Vec<(AbilityName, CastingData)>,
+        target: EntityId,
+    ) {
+        self.execute_combo_on_targets(attacker, combo, &[target], 0);
+    }
+
+    /**
+     * Combo aimed at `targets[primary]`, area abilities also hit the other targets inside their shape.
+     * The attacker walks into range of each ability first, letting the clock run while it does. Q, W, E
+     * and R are cast at the ranks the attacker has learned
+     */
+    pub fn execute_combo_on_targets(
+        &mut self,
+        attacker: EntityId,
+        combo: Vec<(AbilityName, CastingData)>,
+        targets: &[EntityId],
+        primary: usize,
+    ) {
+        for (name, mut data) in combo {
+            if let Some(rank) =
+                Skill::from_ability(&name).and_then(|skill| self[attacker].rank(skill))
+            {
+                data.rank = rank;
+            }
+            let destination = self[targets[primary]].position;
+            let walk_time = self[attacker].time_to_reach(&name, &data, &destination);
+            if let Some(seconds) = walk_time.filter(|seconds| *seconds > 0.0) {
+                self[attacker].move_towards(&destination, seconds);
+                self.advance_time(seconds);
+            }
+            self.execute_ability_on_targets(attacker, &name, targets, primary, &data);
+        }
+    }
+
+    /**
+     * Casts at the primary target first, so on-hits and cooldowns resolve against it, then at each bystander
+     * inside the ability's shape. Returns the damage dealt to each target
+     */
+    pub fn execute_ability_on_targets(
+        &mut self,
+        attacker: EntityId,
+        name: &AbilityName,
+        targets: &[EntityId],
+        primary: usize,
+        casting_data: &CastingData,
+    ) -> Vec<f64> {
+        let mut damages = vec![0.0; targets.len()];
+        let shape = self[attacker].target_shape(name);
+        let caster_position = self[attacker].position;
+        let primary_position = self[targets[primary]].position;
+        damages[primary] = self.execute_ability(attacker, name, targets[primary], casting_data);
+
+        let bystander_data = CastingData {
+            bystander: true,
+            ..casting_data.clone()
+        };
+        for (index, target) in targets.iter().enumerate() {
+            if index == primary
+                || !shape.contains(&caster_position, &primary_position, &self[*target].position)
+            {
+                continue;
+            }
+            damages[index] = self.execute_ability(attacker, name, *target, &bystander_data);
+        }
+        return damages;
+    }
+
+    /**
+     * Casts the ability on the target, returns the damage it dealt
+     */
+    pub fn execute_ability(
+        &mut self,
+        attacker: EntityId,
+        name: &AbilityName,
+        target: EntityId,
+        casting_data: &CastingData,
+    ) -> f64 {
+        self.tick_effects(target);
+        let initial_health = self[target].current_health;
+        let caster = &self[attacker];
+        if !caster.has_learned(name) {
+            return 0.0;
+        }
+        // the caster has no such ability, ie a champion without a w
+        if !caster.abilities.data.contains_key(name) && !STATIC_ABILITIES.contains_key(name) {
+            return 0.0;
+        }
+        let is_cast = matches!(
+            name,
+            AbilityName::AUTO
+                | AbilityName::Q
+                | AbilityName::W
+                | AbilityName::E
+                | AbilityName::R
+                | AbilityName::Summoner(_)
+                | AbilityName::ItemActive(_)
+        );
+        if is_cast && caster.is_crowd_controlled() {
+            return 0.0;
+        }
+        if self[target].is_immune_to(name) {
+            return 0.0;
+        }
+        // bystanders are reached by the ability's shape rather than its range
+        if !casting_data.bystander && !caster.in_range(name, casting_data, &self[target].position) {
+            return 0.0;
+        }
+        match name {
+            AbilityName::AUTO => {
+                self.process_on_hit_effects(attacker, |mode| matches!(mode, OnHitActivation::Auto));
+                self.process_on_auto_effects(attacker, target);
+                let every_auto: Vec<AbilityName> = self[attacker]
+                    .on_hit_item_effects
+                    .iter()
+                    .filter(|effect| matches!(effect.mode, OnHitActivation::EveryAuto))
+                    .map(|on_hit| on_hit.name.clone())
+                    .collect();
+                every_auto.iter().for_each(|name| {
+                    self.execute_ability(attacker, name, target, &CastingData::default());
+                });
+            }
+            AbilityName::Q
+            | AbilityName::W
+            | AbilityName::E
+            | AbilityName::R
+            | AbilityName::ItemActive(_) => {
+                self.process_on_hit_effects(attacker, |mode| {
+                    matches!(mode, OnHitActivation::ActiveSpell)
+                });
+            }
+            _ => {}
+        }
+        // cloned out so the ability is free to change its caster's abilities
+        match self[attacker].abilities.data.get(name).cloned() {
+            Some(func) => func(self, attacker, target, casting_data),
+            None => STATIC_ABILITIES[name](self, attacker, target, casting_data),
+        }
+        self.fire_triggered_abilities(target);
+        // runes react to the damage just dealt, so exposure / stacks start with the next hit
+        if matches!(
+            name,
+            AbilityName::AUTO | AbilityName::Q | AbilityName::W | AbilityName::E | AbilityName::R
+        ) {
+            process_rune_hooks(self, attacker, name, target);
+            self.fire_triggered_abilities(target);
+        }
+        self.fire_triggered_abilities(attacker);
+        return initial_health - self[target].current_health;
+    }
+
+    /**
+     * Casts the abilities that stack thresholds on the entity triggered, ie denting blows' third hit
+     */
+    pub fn fire_triggered_abilities(&mut self, id: EntityId) {
+        for ability in self[id].take_triggered_abilities() {
+            self.execute_ability(ability.attacker, &ability.name, id, &ability.data);
+        }
+    }
+
+    /**
+     * Arms the attacker's on-hit items matching the activation
+     */
+    fn process_on_hit_effects(
+        &mut self,
+        attacker: EntityId,
+        is_activated: impl Fn(&OnHitActivation) -> bool,
+    ) {
+        let on_hit_effects: Vec<EffectData> = self[attacker]
+            .on_hit_item_effects
+            .iter()
+            .filter(|effect| is_activated(&effect.mode))
+            .map(|on_hit| (on_hit, attacker).into())
+            .collect();
+        on_hit_effects.into_iter().for_each(|effect| {
+            self[attacker].upsert_effect(effect);
+        });
+    }
+
+    fn process_on_auto_effects(&mut self, attacker: EntityId, target: EntityId) {
+        let strongest_spellblade = self[attacker].strongest_spellblade(&self[target]);
+        let to_cast: Vec<AbilityEffect> = self[attacker]
+            .valid_effects_mut()
+            .filter_map(|effect| {
+                let mut out: Option<AbilityEffect> = None;
+                if let EffectResult::EmpowerNextAttack(result) = &mut effect.result {
+                    if let EmpowerState::Active(ability, cd) = &result {
+                        effect.expiry = TIME.with(|time| *time.borrow() + cd);
+                        // spellblades share one proc, the rest go on cooldown unused
+                        if !ability.name.is_spellblade()
+                            || strongest_spellblade.as_ref() == Some(&ability.name)
+                        {
+                            out = Some(ability.clone());
+                        }
+                    }
+                    effect.result = EffectResult::EmpowerNextAttack(EmpowerState::Cooldown);
+                }
+                return out;
+            })
+            .collect();
+
+        to_cast.iter().for_each(|ability| {
+            self.execute_ability(ability.attacker, &ability.name, target, &ability.data);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        champions::champion::NamedClosures,
+        target::{DamageOverTime, DotPolicy},
+    };
+    use approx::assert_relative_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    fn test_world_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<World>();
+    }
+
+    #[rstest]
+    fn test_spawn_hands_out_distinct_ids() {
+        let mut world = World::new();
+        let first = world.spawn(Champion::new_dummy());
+        let second = world.spawn(Champion::new_dummy_with_resist(50.0, 0.0));
+        assert_ne!(first, second);
+        assert_eq!(vec![first, second], world.ids().collect::<Vec<_>>());
+        assert_relative_eq!(50.0, world[second].get_base_armor());
+    }
+
+    #[rstest]
+    // a burn whose source is the burning champion itself still ticks with its stats
+    fn test_self_inflicted_damage_over_time() {
+        let mut world = World::new();
+        let mut champion = Champion::new_dummy_with_resist(100.0, 0.0);
+        champion.stats.lethality = 1000.0;
+        let champion = world.spawn(champion);
+        DamageOverTime::new(
+            champion,
+            100.0,
+            DamageType::Physical,
+            1.0,
+            1.0,
+            DotPolicy::Refresh,
+        )
+        .upsert_to_champ(&mut world[champion], "Burn");
+        world.advance_time(1.0);
+        assert_relative_eq!(100.0, world[champion].get_missing_health());
+    }
+
+    #[rstest]
+    fn test_ability_without_closure_does_nothing() {
+        let mut world = World::new();
+        let leblanc = Champion::new(
+            "Leblanc".to_string(),
+            6,
+            [1, 1, 1, 1],
+            NamedClosures::default(),
+        );
+        let leblanc = world.spawn(leblanc);
+        let target = world.spawn(Champion::new_dummy());
+        let damage =
+            world.execute_ability(leblanc, &AbilityName::Q, target, &CastingData::default());
+        assert_eq!(0.0, damage);
+    }
+}
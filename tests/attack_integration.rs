@@ -2,20 +2,23 @@ use practice_tooled::attack;
 
 #[cfg(test)]
 mod tests {
-    use std::{cell::RefCell, rc::Rc};
-
     use super::*;
     use approx::assert_relative_eq;
     use attack::*;
     use practice_tooled::{
         armor_reducer::ArmorReducer,
         champions::{
-            champion::{AbilityName, CastingData, Champion},
+            champion::{AbilityName, CastingData, Champion, NamedClosures},
+            leblanc::Leblanc,
             Vi,
         },
-        load_wiki_item::apply_item_to_champ,
-        target::VitalityData,
+        geometry::Position,
+        item_effects::WikiDamage,
+        load_wiki_item::{apply_item_to_champ, load_wiki_item_effect},
+        target::{CrowdControl, DamageType, Target, VitalityData},
         time_manager::TIME,
+        units::{MinionKind, MonsterKind, TurretKind, UnitKind},
+        world::{EntityId, World},
     };
     use rstest::rstest;
 
@@ -65,49 +68,43 @@ mod tests {
         let level = 6;
         let mut vi_data = Vi::new();
         let vi_closures = vi_data.get_name_closures();
-        let vi = Rc::new(RefCell::new(Champion::new(
-            Vi::NAME.to_string(),
-            level,
-            [0, 0, 0, 0],
-            vi_closures,
-        )));
+        let mut vi = Champion::new(Vi::NAME.to_string(), level, [1, 1, 1, 1], vi_closures);
 
-        apply_item_to_champ("Duskblade of Draktharr", &mut vi.borrow_mut());
+        apply_item_to_champ("Duskblade of Draktharr", &mut vi);
 
-        let target = &mut Champion::new_dummy();
-        let first_proc = Champion::execute_ability(
-            Rc::downgrade(&vi),
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
+        let first_proc = world.execute_ability(
+            vi,
             &AbilityName::AUTO,
             target,
             &CastingData {
                 ..Default::default()
             },
-        )
-        .unwrap();
+        );
         TIME.with(|time| *time.borrow_mut() += 20.0);
-        let second_proc = Champion::execute_ability(
-            Rc::downgrade(&vi),
+        let second_proc = world.execute_ability(
+            vi,
             &AbilityName::AUTO,
             target,
             &CastingData {
                 ..Default::default()
             },
-        )
-        .unwrap();
+        );
 
         //first and second duskblade procs do equal damage (due to delay)
         assert_relative_eq!(first_proc, second_proc);
 
         TIME.with(|time| *time.borrow_mut() += 5.0);
-        let third_auto = Champion::execute_ability(
-            Rc::downgrade(&vi),
+        let third_auto = world.execute_ability(
+            vi,
             &AbilityName::AUTO,
             target,
             &CastingData {
                 ..Default::default()
             },
-        )
-        .unwrap();
+        );
         assert!(
             third_auto < second_proc,
             "third auto {:2} shouldnt be a duskblade proc and do less than second {:2}",
@@ -116,6 +113,60 @@ mod tests {
         );
     }
 
+    #[rstest]
+    fn test_nighstalker_wiki_values() {
+        let mut vi_data = Vi::new();
+        let vi_closures = vi_data.get_name_closures();
+        let mut vi = Champion::new(Vi::NAME.to_string(), 6, [1, 1, 1, 1], vi_closures);
+        apply_item_to_champ("Duskblade of Draktharr", &mut vi);
+
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
+        let empowered_auto =
+            world.execute_ability(vi, &AbilityName::AUTO, target, &CastingData::default());
+        TIME.with(|time| *time.borrow_mut() += 1.0);
+        let plain_auto =
+            world.execute_ability(vi, &AbilityName::AUTO, target, &CastingData::default());
+        // vi is melee: {{rd|75|55}} (+ {{rd|30%|25%}} bonus AD) with duskblade's 60 bonus AD
+        assert_relative_eq!(75.0 + 0.3 * 60.0, empowered_auto - plain_auto);
+    }
+
+    #[rstest]
+    #[case("Vi", 99.0)]
+    // duskblade's limit: ranged champions do not apply the slow
+    #[case("Caitlyn", 0.0)]
+    fn test_nightstalker_slow(#[case] champion_name: &str, #[case] expected_slow: f64) {
+        let mut attacker = Champion::new(
+            champion_name.to_string(),
+            6,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        );
+        apply_item_to_champ("Duskblade of Draktharr", &mut attacker);
+
+        let mut world = World::new();
+        let attacker = world.spawn(attacker);
+        let target = world.spawn(Champion::new(
+            Leblanc::NAME.to_string(),
+            6,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        ));
+        let move_speed = world[target].get_move_speed();
+        world.execute_ability(
+            attacker,
+            &AbilityName::AUTO,
+            target,
+            &CastingData::default(),
+        );
+        assert_relative_eq!(
+            move_speed * (1.0 - expected_slow / 100.0),
+            world[target].get_move_speed(),
+            epsilon = 1e-9
+        );
+    }
+
     #[rstest]
     #[case(5.0, 0.0, (true, false))]
     #[case(5.0, 2.0, (true, true))]
@@ -128,27 +179,23 @@ mod tests {
         let level = 6;
         let mut vi_data = Vi::new();
         let vi_closures = vi_data.get_name_closures();
-        let vi = Rc::new(RefCell::new(Champion::new(
-            Vi::NAME.to_string(),
-            level,
-            [0, 0, 0, 0],
-            vi_closures,
-        )));
+        let mut vi = Champion::new(Vi::NAME.to_string(), level, [1, 1, 1, 1], vi_closures);
 
-        apply_item_to_champ("Sheen", &mut vi.borrow_mut());
+        apply_item_to_champ("Sheen", &mut vi);
 
-        let target = &mut Champion::new_dummy();
-        let base_auto = Champion::execute_ability(
-            Rc::downgrade(&vi),
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
+        let base_auto = world.execute_ability(
+            vi,
             &AbilityName::AUTO,
             target,
             &CastingData {
                 ..Default::default()
             },
-        )
-        .unwrap();
-        Champion::execute_ability(
-            Rc::downgrade(&vi),
+        );
+        world.execute_ability(
+            vi,
             &AbilityName::R,
             target,
             &CastingData {
@@ -157,35 +204,33 @@ mod tests {
         );
         TIME.with(|time| *time.borrow_mut() += ability_delay);
 
-        let empowered_auto = Champion::execute_ability(
-            Rc::downgrade(&vi),
+        let empowered_auto = world.execute_ability(
+            vi,
             &AbilityName::AUTO,
             target,
             &CastingData {
                 ..Default::default()
             },
-        )
-        .unwrap();
+        );
         println!("{base_auto} {empowered_auto}");
 
         TIME.with(|time| *time.borrow_mut() += auto_delay);
-        Champion::execute_ability(
-            Rc::downgrade(&vi),
+        world.execute_ability(
+            vi,
             &AbilityName::R,
             target,
             &CastingData {
                 ..Default::default()
             },
         );
-        let second_base_auto = Champion::execute_ability(
-            Rc::downgrade(&vi),
+        let second_base_auto = world.execute_ability(
+            vi,
             &AbilityName::AUTO,
             target,
             &CastingData {
                 ..Default::default()
             },
-        )
-        .unwrap();
+        );
         let (first, second) = expect_empowered;
         if first {
             assert!(base_auto < empowered_auto);
@@ -204,4 +249,306 @@ mod tests {
             assert_relative_eq!(base_auto, second_base_auto);
         }
     }
+
+    #[rstest]
+    #[case(&["Sheen"], 1.0, 0.0)]
+    #[case(&["Trinity Force"], 2.0, 0.0)]
+    #[case(&["Iceborn Gauntlet"], 1.0, 0.0)]
+    #[case(&["Lich Bane"], 0.75, 0.5)]
+    // only the strongest spellblade procs
+    #[case(&["Sheen", "Trinity Force"], 2.0, 0.0)]
+    fn test_spellblade(
+        #[case] item_names: &[&str],
+        #[case] base_ad_ratio: f64,
+        #[case] ap_ratio: f64,
+    ) {
+        let level = 6;
+        let mut vi_data = Vi::new();
+        let vi_closures = vi_data.get_name_closures();
+        let mut vi = Champion::new(Vi::NAME.to_string(), level, [1, 1, 1, 1], vi_closures);
+        item_names
+            .iter()
+            .for_each(|item_name| apply_item_to_champ(item_name, &mut vi));
+
+        let mut world = World::new();
+        let vi = world.spawn(vi);
+        let target = world.spawn(Champion::new_dummy());
+        let auto = |world: &mut World| {
+            world.execute_ability(vi, &AbilityName::AUTO, target, &CastingData::default())
+        };
+        let base_auto = auto(&mut world);
+        // let denting blows' stack fall off
+        TIME.with(|time| *time.borrow_mut() += 5.0);
+        world.execute_ability(vi, &AbilityName::R, target, &CastingData::default());
+        let empowered_auto = auto(&mut world);
+
+        let expected =
+            base_ad_ratio * world[vi].get_base_ad() + ap_ratio * world[vi].get_ability_power();
+        assert_relative_eq!(expected, empowered_auto - base_auto, epsilon = 1e-9);
+    }
+
+    fn dummy_with_item(item_name: &str) -> Champion {
+        let mut attacker = Champion::new_dummy();
+        attacker.stats.base_attack_damage = 100.0;
+        apply_item_to_champ(item_name, &mut attacker);
+        attacker
+    }
+
+    #[rstest]
+    // 12% of current health for melee, with a minimum of 15
+    #[case(1000.0, 120.0)]
+    #[case(500.0, 60.0)]
+    #[case(100.0, 15.0)]
+    fn test_blade_of_the_ruined_king(#[case] current_health: f64, #[case] expected_on_hit: f64) {
+        let attacker = dummy_with_item("Blade of the Ruined King");
+        let mut plain_attacker = Champion::new_dummy();
+        plain_attacker.stats.base_attack_damage = 100.0;
+        plain_attacker.stats.bonus_attack_damage = attacker.stats.bonus_attack_damage;
+
+        let auto = |attacker: Champion| {
+            let mut world = World::new();
+            let attacker = world.spawn(attacker);
+            let target = world.spawn(Champion::new_dummy());
+            world[target].current_health = current_health;
+            world.execute_ability(
+                attacker,
+                &AbilityName::AUTO,
+                target,
+                &CastingData::default(),
+            )
+        };
+        let on_hit = auto(attacker) - auto(plain_attacker);
+        assert_relative_eq!(expected_on_hit, on_hit, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    // executes below 5% of the dummy's 1000 max health
+    #[case(55.0, 0.0)]
+    #[case(45.0, 55.0)]
+    fn test_collector_execute(#[case] damage: f64, #[case] expected_health: f64) {
+        let attacker = dummy_with_item("The Collector");
+        let target = &mut Champion::new_dummy();
+        target.current_health = 100.0;
+        target.receive_damage(&attacker, damage);
+        assert_relative_eq!(expected_health, target.current_health);
+    }
+
+    #[rstest]
+    // melee holders slow the primary target for double 15% (+ 0.4% per 100 maximum health), at most 99%
+    #[case(0.0, 2.0 * (15.0 + 0.4 * 14.0))]
+    #[case(20000.0, 99.0)]
+    fn test_iceborn_gauntlet_slow(#[case] bonus_health: f64, #[case] expected: f64) {
+        let mut holder = dummy_with_item("Iceborn Gauntlet");
+        holder.stats.health += bonus_health;
+        let mut world = World::new();
+        let holder = world.spawn(holder);
+        let target = world.spawn(Champion::new_dummy());
+        world.execute_ability(
+            holder,
+            &AbilityName::SpellbladeIcebornGauntlet,
+            target,
+            &CastingData::default(),
+        );
+        assert_relative_eq!(expected, world[target].get_slow());
+    }
+
+    #[rstest]
+    // the frost field's primary target deals 10% less damage to the holder, others take it in full
+    fn test_iceborn_gauntlet_reduces_damage_to_holder() {
+        let mut world = World::new();
+        let holder = world.spawn(dummy_with_item("Iceborn Gauntlet"));
+        let target = world.spawn(Champion::new_dummy());
+        let bystander = world.spawn(Champion::new_dummy());
+        world.execute_ability(
+            holder,
+            &AbilityName::SpellbladeIcebornGauntlet,
+            target,
+            &CastingData::default(),
+        );
+        let full_damage = |world: &World, id: EntityId| {
+            world[id].mitigated_damage(&world[target], 100.0, DamageType::Physical)
+        };
+        let (holder_damage, bystander_damage) =
+            (full_damage(&world, holder), full_damage(&world, bystander));
+        let holder_health = world[holder].current_health;
+        world.deal_damage(target, holder, 100.0);
+        world.deal_damage(target, bystander, 100.0);
+        assert_relative_eq!(
+            0.9 * holder_damage,
+            holder_health - world[holder].current_health
+        );
+        assert_relative_eq!(bystander_damage, world[bystander].get_missing_health());
+    }
+
+    #[rstest]
+    // even move speed never closes the gap, boots or a slow do
+    #[case(&[], None, None)]
+    #[case(&["Boots"], None, Some((600.0 - 125.0) / 25.0))]
+    #[case(&[], Some(50.0), Some((600.0 - 125.0) / 170.0))]
+    fn test_chase_fleeing_leblanc(
+        #[case] vi_items: &[&str],
+        #[case] leblanc_slow: Option<f64>,
+        #[case] expected: Option<f64>,
+    ) {
+        let mut vi = Champion::new(
+            Vi::NAME.to_string(),
+            6,
+            [0, 0, 0, 0],
+            Vi::new().get_name_closures(),
+        );
+        vi_items
+            .iter()
+            .for_each(|item_name| apply_item_to_champ(item_name, &mut vi));
+        let mut leblanc = Champion::new(
+            Leblanc::NAME.to_string(),
+            6,
+            [0, 0, 0, 0],
+            NamedClosures::default(),
+        );
+        leblanc.position = Position::new(600.0, 0.0);
+        if let Some(percent) = leblanc_slow {
+            leblanc.apply_crowd_control("Slow", CrowdControl::Slow(percent), 5.0);
+        }
+        let range = vi.stats.attack_range;
+        let observed = vi.time_to_catch(&leblanc, range);
+        assert_eq!(expected.is_some(), observed.is_some());
+        if let (Some(expected), Some(observed)) = (expected, observed) {
+            assert_relative_eq!(expected, observed, epsilon = 1e-9);
+        }
+    }
+
+    fn vi_at_level(level: u8) -> (World, EntityId) {
+        let mut world = World::new();
+        let vi = world.spawn(Champion::new(
+            Vi::NAME.to_string(),
+            level,
+            [1, 1, 1, 1],
+            Vi::new().get_name_closures(),
+        ));
+        (world, vi)
+    }
+
+    #[rstest]
+    fn test_structures_only_take_auto_damage() {
+        let (mut world, vi) = vi_at_level(6);
+        let turret = world.spawn(Champion::new_turret(TurretKind::Outer));
+        let mut cast =
+            |name: AbilityName| world.execute_ability(vi, &name, turret, &CastingData::new(0));
+        assert_eq!(0.0, cast(AbilityName::Q));
+        assert!(cast(AbilityName::AUTO) > 0.0);
+    }
+
+    #[rstest]
+    fn test_baron_reduces_champion_damage() {
+        let (mut world, vi) = vi_at_level(6);
+        let baron = world.spawn(Champion::new_monster(MonsterKind::BaronNashor, 1));
+        let stats = MonsterKind::BaronNashor.stats();
+        let dummy = world.spawn(Champion::new_dummy_with_resist(
+            stats.armor,
+            stats.magic_resist,
+        ));
+        let mut auto = |target: EntityId| {
+            world.execute_ability(vi, &AbilityName::AUTO, target, &CastingData::default())
+        };
+        let reduced = 1.0 - stats.damage_reduction / 100.0;
+        assert_relative_eq!(auto(dummy) * reduced, auto(baron), epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_mists_edge_capped_against_monsters() {
+        let (mut world, vi) = vi_at_level(6);
+        apply_item_to_champ("Blade of the Ruined King", &mut world[vi]);
+        let gromp = world.spawn(Champion::new_monster(MonsterKind::Gromp, 1));
+        let damage =
+            world.execute_ability(vi, &AbilityName::MistsEdge, gromp, &CastingData::default());
+        let expected = world[gromp].mitigated_damage(&world[vi], 60.0, DamageType::Physical);
+        assert_relative_eq!(expected, damage, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    // 12% of 50 current health is below the minimum of 15, champions aren't capped at 60
+    #[case(50.0, 15.0)]
+    #[case(2000.0, 0.12 * 2000.0)]
+    fn test_mists_edge_bounds_against_champions(
+        #[case] current_health: f64,
+        #[case] expected_raw: f64,
+    ) {
+        let (mut world, vi) = vi_at_level(6);
+        apply_item_to_champ("Blade of the Ruined King", &mut world[vi]);
+        let mut target = Champion::new_dummy();
+        target.stats.health = 5000.0;
+        target.current_health = current_health;
+        let target = world.spawn(target);
+        let damage =
+            world.execute_ability(vi, &AbilityName::MistsEdge, target, &CastingData::default());
+        let expected =
+            world[target].mitigated_damage(&world[vi], expected_raw, DamageType::Physical);
+        assert_relative_eq!(expected, damage, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_divine_sunderer_bounds() {
+        let (mut world, vi) = vi_at_level(6);
+        apply_item_to_champ("Divine Sunderer", &mut world[vi]);
+        let base_ad = world[vi].get_base_ad();
+        let spellblade = AbilityName::SpellbladeDivineSunderer;
+
+        // 125% base AD and 6% of 100 maximum health is below the minimum of 150% base AD
+        let mut frail = Champion::new_dummy();
+        frail.stats.health = 100.0;
+        frail.current_health = 100.0;
+        let frail = world.spawn(frail);
+        let damage = world.execute_ability(vi, &spellblade, frail, &CastingData::default());
+        let expected =
+            world[frail].mitigated_damage(&world[vi], 1.5 * base_ad, DamageType::Physical);
+        assert_relative_eq!(expected, damage, epsilon = 1e-9);
+
+        // champions aren't capped
+        let mut tank = Champion::new_dummy();
+        tank.stats.health = 20000.0;
+        tank.current_health = 20000.0;
+        let tank = world.spawn(tank);
+        let damage = world.execute_ability(vi, &spellblade, tank, &CastingData::default());
+        let expected = world[tank].mitigated_damage(
+            &world[vi],
+            1.25 * base_ad + 0.06 * 20000.0,
+            DamageType::Physical,
+        );
+        assert_relative_eq!(expected, damage, epsilon = 1e-9);
+
+        // baron's maximum health goes well past the 250% base AD maximum against monsters
+        let baron = world.spawn(Champion::new_monster(MonsterKind::BaronNashor, 6));
+        let damage = world.execute_ability(vi, &spellblade, baron, &CastingData::default());
+        let expected =
+            world[baron].mitigated_damage(&world[vi], 2.5 * base_ad, DamageType::Physical);
+        assert_relative_eq!(expected, damage, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_bonus_health_scaling() {
+        let (mut world, vi) = vi_at_level(6);
+        apply_item_to_champ("Sunfire Aegis", &mut world[vi]);
+        apply_item_to_champ("Warmog's Armor", &mut world[vi]);
+        let bonus_health = world[vi].stat_sheet().health.bonus;
+        assert!(bonus_health > 0.0);
+        let target = world.spawn(Champion::new_dummy());
+
+        // immolate deals 15 (+ 1.75% bonus health) magic damage
+        let effect = load_wiki_item_effect("Sunfire Aegis", "Immolate").unwrap();
+        let damage = WikiDamage::from_effect(effect).mitigated(&world[vi], &world[target]);
+        let expected = world[target].mitigated_damage(
+            &world[vi],
+            15.0 + 0.0175 * bonus_health,
+            DamageType::Magic,
+        );
+        assert_relative_eq!(expected, damage, epsilon = 1e-9);
+    }
+
+    #[rstest]
+    fn test_minions_grow_with_game_time() {
+        let early = Champion::new_minion(MinionKind::Caster, 0.0);
+        let late = Champion::new_minion(MinionKind::Caster, 1200.0);
+        assert!(late.get_max_health() > early.get_max_health());
+        assert_eq!(UnitKind::Minion(MinionKind::Caster), late.unit_kind());
+    }
 }